@@ -1,3 +1,4 @@
+-- The schema as it was first deployed from schema.sql, existing databases already have it
 CREATE TABLE IF NOT EXISTS public.games
 (
     id TEXT NOT NULL,
@@ -0,0 +1,53 @@
+-- Columns that were added to schema.sql after the first deployment. Databases created from a
+-- later schema.sql already have some of them, which is why every step checks first. schema.sql was
+-- only applied to new databases, so existing ones get these columns from this migration alone and
+-- the code that uses them must not be deployed without it.
+ALTER TABLE public.games ADD COLUMN IF NOT EXISTS offer_from DATE;
+ALTER TABLE public.games ADD COLUMN IF NOT EXISTS redeem_on TEXT;
+ALTER TABLE public.games ADD COLUMN IF NOT EXISTS offer_kind TEXT NOT NULL DEFAULT 'Keep';
+ALTER TABLE public.games ADD COLUMN IF NOT EXISTS image_url TEXT;
+ALTER TABLE public.games ADD COLUMN IF NOT EXISTS original_price_minor BIGINT;
+ALTER TABLE public.games ADD COLUMN IF NOT EXISTS original_price_currency TEXT;
+
+ALTER TABLE public.platform_posts ADD COLUMN IF NOT EXISTS post_kind TEXT NOT NULL DEFAULT 'Free';
+
+-- Prices used to be stored as displayed, e.g. "$19.99" or "19,99€". They are parsed like
+-- Price::parse does: a currency code wins over a symbol, dollars of other countries are told apart
+-- by their prefix and a separator followed by two digits is the decimal separator.
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = 'public' AND table_name = 'games' AND column_name = 'original_price'
+    ) THEN
+        UPDATE public.games SET original_price_currency = COALESCE(
+                substring(original_price FROM '(?:^|[^A-Za-z])([A-Z]{3})(?:[^A-Za-z]|$)'),
+                CASE
+                    WHEN original_price LIKE '%CA$%' THEN 'CAD'
+                    WHEN original_price LIKE '%A$%' OR original_price LIKE '%AU$%' THEN 'AUD'
+                    WHEN original_price LIKE '%NZ$%' THEN 'NZD'
+                    WHEN original_price LIKE '%HK$%' THEN 'HKD'
+                    WHEN original_price LIKE '%MX$%' THEN 'MXN'
+                    WHEN original_price LIKE '%R$%' THEN 'BRL'
+                    WHEN original_price LIKE '%$%' THEN 'USD'
+                    WHEN original_price LIKE '%€%' THEN 'EUR'
+                    WHEN original_price LIKE '%£%' THEN 'GBP'
+                    WHEN original_price LIKE '%¥%' THEN 'JPY'
+                END
+            )
+        WHERE original_price_currency IS NULL;
+
+        UPDATE public.games SET original_price_minor = CASE
+                WHEN original_price_currency IN ('JPY', 'KRW')
+                    OR regexp_replace(original_price, '[^0-9.,]', '', 'g') ~ '[.,][0-9]{2}$'
+                    THEN NULLIF(regexp_replace(original_price, '[^0-9]', '', 'g'), '')::BIGINT
+                ELSE NULLIF(regexp_replace(original_price, '[^0-9]', '', 'g'), '')::BIGINT * 100
+            END
+        WHERE original_price_minor IS NULL AND original_price_currency IS NOT NULL;
+
+        -- Prices like "Free" have no amount, they don't have a currency either
+        UPDATE public.games SET original_price_currency = NULL WHERE original_price_minor IS NULL;
+
+        ALTER TABLE public.games DROP COLUMN original_price;
+    END IF;
+END $$;
@@ -1,33 +1,73 @@
-use std::sync::Arc;
-use tokio_postgres::{Client, Row};
-use utils::model::{Game, GameStore, GameType, PartialGame, PostedPlatform};
+use std::num::{NonZeroUsize, ParseIntError};
+use std::time::Duration;
+use deadpool_postgres::{Manager, ManagerConfig, Pool, PoolError, RecyclingMethod, Runtime};
+use serde::Serialize;
+use tokio_postgres::Row;
+use tokio_postgres::types::ToSql;
+use utils::model::{Game, GamesPage, GameStore, GameType, Launcher, OfferKind, PartialGame, PostedPlatform, Price};
+use crate::migrations;
+use crate::query::{Cursor, CursorKey, GamesQuery, GamesSort, ValueQuery};
 
 const MAX_RETRIES: u64 = 10;
 const RETRY_DELAY: u64 = 5;
+const MAX_PAGE_SIZE: i64 = 500;
+const DEFAULT_POOL_SIZE: usize = 16;
+const POOL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Which offers a game listing is restricted to.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum GamesStatus {
+    Any,
+    Active,
+    Upcoming,
+}
+
+/// Sum of the original prices of all games given away in one currency.
+#[derive(Debug, Serialize)]
+pub(crate) struct PriceTotal {
+    pub currency: String,
+    pub amount_minor: i64,
+    pub games: i64,
+}
 
 #[derive(Clone)]
 pub(crate) struct Database {
-    client: Arc<Client>,
+    pool: Pool,
 }
 
 impl Database {
     pub async fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        let pool_size = parse_pool_size(std::env::var("DATABASE_POOL_SIZE").ok().as_deref())
+            .map_err(|e| format!("Invalid DATABASE_POOL_SIZE: {e}"))?;
+
+        // Connections are verified with a test query before they're handed out again, the ones that
+        // broke, e.g. because Postgres restarted, are replaced with new connections
+        let manager = Manager::from_config(
+            url.parse::<tokio_postgres::Config>()?,
+            postgres::NoTls,
+            ManagerConfig { recycling_method: RecyclingMethod::Verified },
+        );
+        let pool = Pool::builder(manager)
+            .max_size(pool_size)
+            .runtime(Runtime::Tokio1)
+            .wait_timeout(Some(POOL_TIMEOUT))
+            .create_timeout(Some(POOL_TIMEOUT))
+            .recycle_timeout(Some(POOL_TIMEOUT))
+            .build()?;
 
         let mut retries = 0;
         loop {
-            match tokio_postgres::connect(url.as_str(), postgres::NoTls).await {
-                Ok((client, connection)) => {
-                    tokio::spawn(async move {
-                        if let Err(e) = connection.await {
-                            log::error!("Connection error: {e}");
-                        }
-                    });
-
+            match pool.get().await {
+                Ok(mut client) => {
                     log::info!("Connected to database.");
 
+                    if let Err(e) = migrations::run(&mut client).await {
+                        return Err(format!("Database migration failed: {e}").into());
+                    }
+
                     return Ok(Database {
-                        client: Arc::new(client),
+                        pool,
                     });
                 }
                 Err(e) => {
@@ -42,19 +82,17 @@ impl Database {
         }
     }
 
-    pub async fn add_game(&self, game: &Game) -> Result<(), tokio_postgres::Error> {
-        let partial = PartialGame { id: game.id.clone(), store: game.store.clone() };
-        if self.game_exists(&partial).await? {
-            return Ok(());
-        }
-
+    /// Returns `false` if the game already exists, it's left unchanged then.
+    pub async fn add_game(&self, game: &Game) -> Result<bool, PoolError> {
+        let client = self.pool.get().await?;
         let query = r#"
             INSERT INTO games
-            (id, store, created_at, title, identifier, url, original_price, offer_until, game_type)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);"#;
+            (id, store, created_at, title, identifier, url, original_price_minor, original_price_currency, offer_from, offer_until, game_type, redeem_on, offer_kind, image_url)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (id, store) DO NOTHING;"#;
         let now = chrono::Utc::now().date_naive();
 
-        self.client.execute(
+        let inserted = client.execute(
             query,
             &[
                 &game.id,
@@ -63,54 +101,112 @@ impl Database {
                 &game.title,
                 &game.identifier,
                 &game.url,
-                &game.original_price,
+                &game.original_price.as_ref().map(|price| price.amount_minor),
+                &game.original_price.as_ref().map(|price| price.currency.clone()),
+                &game.offer_from,
                 &game.offer_until,
                 &game.game_type.to_string(),
+                &game.redeem_on.as_ref().map(|launcher| launcher.to_string()),
+                &game.offer_kind.to_string(),
+                &game.image_url,
             ],
         )
             .await?;
 
-        Ok(())
+        Ok(inserted > 0)
     }
 
-    pub async fn remove_game(&self, game: &PartialGame) -> Result<(), tokio_postgres::Error> {
-        let query = r#"DELETE FROM games WHERE id = $1 AND store = $2;"#;
+    /// Returns `false` if there was no such game.
+    /// Also removes the game's posts, so it's posted again when it's added again.
+    pub async fn remove_game(&self, game: &PartialGame) -> Result<bool, PoolError> {
+        let mut client = self.pool.get().await?;
+        let store = game.store.to_string();
 
-        self.client.execute(query, &[&game.id, &game.store.to_string()])
+        // Either both are removed or neither, a game must not lose its posts and stay in the database
+        let transaction = client.transaction().await?;
+        let removed = transaction.execute(r#"DELETE FROM games WHERE id = $1 AND store = $2;"#, &[&game.id, &store])
             .await?;
+        transaction.execute(r#"DELETE FROM platform_posts WHERE game_id = $1 AND game_store = $2;"#, &[&game.id, &store])
+            .await?;
+        transaction.commit().await?;
 
-        self.remove_posted(game).await?;
-
-        Ok(())
+        Ok(removed > 0)
     }
 
-    pub async fn _remove_inactive_games(&self) -> Result<(), tokio_postgres::Error> {
+    pub async fn _remove_inactive_games(&self) -> Result<(), PoolError> {
+        let client = self.pool.get().await?;
         let query = r#"DELETE FROM games WHERE offer_until < CURRENT_DATE;"#;
 
-        self.client.execute(query, &[]).await?;
+        client.execute(query, &[]).await?;
 
         Ok(())
     }
 
-    pub async fn game_exists(&self, game: &PartialGame) -> Result<bool, tokio_postgres::Error> {
+    pub async fn game_exists(&self, game: &PartialGame) -> Result<bool, PoolError> {
+        let client = self.pool.get().await?;
         let query = r#"SELECT * FROM games WHERE id = $1 AND store = $2;"#;
 
-        match self
-            .client
-            .query(query, &[&game.id, &game.store.to_string()])
-            .await
-        {
-            Ok(rows) => Ok(!rows.is_empty()),
-            Err(e) => Err(e),
+        let rows = client.query(query, &[&game.id, &game.store.to_string()]).await?;
+
+        Ok(!rows.is_empty())
+    }
+
+    pub async fn get_game(&self, game: &PartialGame) -> Result<Option<Game>, PoolError> {
+        let client = self.pool.get().await?;
+        let query = r#"SELECT * FROM games WHERE id = $1 AND store = $2;"#;
+
+        match client.query_opt(query, &[&game.id, &game.store.to_string()]).await? {
+            Some(row) => Ok(Some(row_to_game(&row)?)),
+            None => Ok(None),
         }
     }
 
-    pub async fn get_all_games(&self) -> Result<Vec<Game>, tokio_postgres::Error> {
-        let query = r#"SELECT * FROM games"#;
+    pub async fn get_games(&self, filter: &GamesQuery, status: GamesStatus, cursor: Option<&Cursor>) -> Result<GamesPage, PoolError> {
+        let client = self.pool.get().await?;
+        let sql = build_games_sql(filter, status, cursor);
+        let params = sql.params.iter()
+            .map(|param| param.as_ref() as &(dyn ToSql + Sync))
+            .collect::<Vec<_>>();
+
+        let total: i64 = client.query_one(sql.count_query.as_str(), &params[..sql.filter_params]).await?.try_get(0)?;
+        let mut rows = client.query(sql.query.as_str(), &params).await?;
+
+        let next_cursor = match sql.limit {
+            Some(limit) if rows.len() as i64 > limit => {
+                rows.truncate(limit as usize);
+                let last = rows.last().expect("limit is at least 1");
+                let key = match filter.sort() {
+                    GamesSort::PriceAsc | GamesSort::PriceDesc => CursorKey::Price(last.try_get("sort_key")?),
+                    GamesSort::TitleAsc | GamesSort::TitleDesc => CursorKey::Title(last.try_get("sort_key")?),
+                    _ => CursorKey::Date(last.try_get("sort_key")?),
+                };
+
+                Some(Cursor { sort: filter.sort(), key, store: last.try_get("store")?, id: last.try_get("id")? }.encode())
+            },
+            _ => None,
+        };
+
+        let mut games = Vec::with_capacity(rows.len());
+        for row in rows {
+            games.push(row_to_game(&row)?);
+        }
+
+        Ok(GamesPage {
+            games,
+            total,
+            next_cursor,
+        })
+    }
+
+    pub async fn get_active_games(&self) -> Result<Vec<Game>, PoolError> {
+        let client = self.pool.get().await?;
+        let query = r#"SELECT * FROM games
+            WHERE offer_until > CURRENT_DATE
+            AND (offer_from IS NULL OR offer_from <= CURRENT_DATE)"#;
 
         let mut games = Vec::new();
 
-        let rows = self.client.query(query, &[]).await?;
+        let rows = client.query(query, &[]).await?;
 
         for row in rows {
             let game = row_to_game(&row)?;
@@ -120,12 +216,13 @@ impl Database {
         Ok(games)
     }
 
-    pub async fn get_active_games(&self) -> Result<Vec<Game>, tokio_postgres::Error> {
-        let query = r#"SELECT * FROM games WHERE offer_until > CURRENT_DATE"#;
+    pub async fn get_upcoming_games(&self) -> Result<Vec<Game>, PoolError> {
+        let client = self.pool.get().await?;
+        let query = r#"SELECT * FROM games WHERE offer_from > CURRENT_DATE"#;
 
         let mut games = Vec::new();
 
-        let rows = self.client.query(query, &[]).await?;
+        let rows = client.query(query, &[]).await?;
 
         for row in rows {
             let game = row_to_game(&row)?;
@@ -135,47 +232,194 @@ impl Database {
         Ok(games)
     }
 
-    pub async fn is_posted(&self, post_data: &PostedPlatform) -> Result<bool, tokio_postgres::Error> {
+    pub async fn get_given_away_value(&self, filter: &ValueQuery) -> Result<Vec<PriceTotal>, PoolError> {
+        let client = self.pool.get().await?;
+        let query = r#"SELECT
+                original_price_currency AS currency,
+                SUM(original_price_minor)::BIGINT AS amount_minor,
+                COUNT(*) AS games
+            FROM games
+            WHERE original_price_currency IS NOT NULL
+            AND offer_kind = 'Keep'
+            AND (offer_from IS NULL OR offer_from <= CURRENT_DATE)
+            AND ($1::DATE IS NULL OR created_at >= $1)
+            AND ($2::DATE IS NULL OR created_at <= $2)
+            GROUP BY original_price_currency
+            ORDER BY amount_minor DESC"#;
+
+        let rows = client.query(query, &[&filter.since, &filter.until]).await?;
+
+        let mut totals = Vec::with_capacity(rows.len());
+        for row in rows {
+            totals.push(PriceTotal {
+                currency: row.try_get("currency")?,
+                amount_minor: row.try_get("amount_minor")?,
+                games: row.try_get("games")?,
+            });
+        }
+
+        Ok(totals)
+    }
+
+    pub async fn is_posted(&self, post_data: &PostedPlatform) -> Result<bool, PoolError> {
+        let client = self.pool.get().await?;
         let query = r#"SELECT * FROM platform_posts
             WHERE game_id = $1
             AND game_store = $2
-            AND platform = $3;
+            AND platform = $3
+            AND post_kind = $4;
         "#;
 
-        match self
-            .client
-            .query(query, &[&post_data.game_id, &post_data.game_store.to_string(), &post_data.platform])
-            .await
-        {
-            Ok(rows) => Ok(!rows.is_empty()),
-            Err(e) => Err(e),
-        }
+        let rows = client.query(query, &[&post_data.game_id, &post_data.game_store.to_string(), &post_data.platform, &post_data.kind.to_string()]).await?;
+
+        Ok(!rows.is_empty())
     }
 
-    pub async fn add_posted(&self, post_data: &PostedPlatform) -> Result<bool, tokio_postgres::Error> {
+    /// Returns `false` if the game was already recorded as posted on the platform.
+    pub async fn add_posted(&self, post_data: &PostedPlatform) -> Result<bool, PoolError> {
+        let client = self.pool.get().await?;
         let query = r#"INSERT INTO platform_posts
-            (game_id, game_store, platform)
-            VALUES ($1, $2, $3);"#;
+            (game_id, game_store, platform, post_kind, posted_at)
+            SELECT $1, $2, $3, $4, NOW()
+            WHERE NOT EXISTS (
+                SELECT 1 FROM platform_posts
+                WHERE game_id = $1
+                AND game_store = $2
+                AND platform = $3
+                AND post_kind = $4
+            );"#;
+
+        let inserted = client.execute(query, &[&post_data.game_id, &post_data.game_store.to_string(), &post_data.platform, &post_data.kind.to_string()]).await?;
+
+        Ok(inserted > 0)
+    }
+}
 
-        match self.client.execute(query, &[&post_data.game_id, &post_data.game_store.to_string(), &post_data.platform]).await {
-            Ok(_) => Ok(true),
-            Err(e) => Err(e),
-        }
+/// An unset or empty size falls back to the default, a pool without connections is rejected.
+fn parse_pool_size(size: Option<&str>) -> Result<usize, ParseIntError> {
+    match size {
+        Some(size) if !size.is_empty() => Ok(size.parse::<NonZeroUsize>()?.get()),
+        _ => Ok(DEFAULT_POOL_SIZE),
     }
+}
 
-    pub async fn remove_posted(&self, game: &PartialGame) -> Result<(), tokio_postgres::Error> {
-        let query = r#"DELETE FROM platform_posts WHERE game_id = $1 AND game_store = $2;"#;
+/// Queries of a game listing. Both share `params`, but the count query only uses the first
+/// `filter_params` of them, it counts the games of all pages.
+struct GamesSql {
+    count_query: String,
+    query: String,
+    params: Vec<Box<dyn ToSql + Sync + Send>>,
+    filter_params: usize,
+    /// One game more than this is fetched, which tells whether there is another page
+    limit: Option<i64>,
+}
 
-        self.client.execute(query, &[&game.id, &game.store.to_string()])
-            .await?;
+fn build_games_sql(filter: &GamesQuery, status: GamesStatus, cursor: Option<&Cursor>) -> GamesSql {
+    let mut conditions = vec![match status {
+        GamesStatus::Any => "TRUE".to_string(),
+        GamesStatus::Active => "offer_until > CURRENT_DATE AND (offer_from IS NULL OR offer_from <= CURRENT_DATE)".to_string(),
+        GamesStatus::Upcoming => "offer_from > CURRENT_DATE".to_string(),
+    }];
+    let mut params: Vec<Box<dyn ToSql + Sync + Send>> = vec![];
+
+    if let Some(ref currency) = filter.currency {
+        params.push(Box::new(currency.clone()));
+        conditions.push(format!("original_price_currency = ${}", params.len()));
+    }
+    if let Some(min_price) = filter.min_price {
+        params.push(Box::new(min_price));
+        conditions.push(format!("original_price_minor >= ${}", params.len()));
+    }
+    if let Some(max_price) = filter.max_price {
+        params.push(Box::new(max_price));
+        conditions.push(format!("original_price_minor <= ${}", params.len()));
+    }
+    if let Some(ref store) = filter.store {
+        params.push(Box::new(GameStore::from(store.clone()).to_string()));
+        conditions.push(format!("store = ${}", params.len()));
+    }
+    if let Some(ref game_type) = filter.game_type {
+        params.push(Box::new(GameType::from(game_type.clone()).to_string()));
+        conditions.push(format!("game_type = ${}", params.len()));
+    }
+    if let Some(created_from) = filter.created_from {
+        params.push(Box::new(created_from));
+        conditions.push(format!("created_at >= ${}", params.len()));
+    }
+    if let Some(created_to) = filter.created_to {
+        params.push(Box::new(created_to));
+        conditions.push(format!("created_at <= ${}", params.len()));
+    }
+    if let Some(offer_until_from) = filter.offer_until_from {
+        params.push(Box::new(offer_until_from));
+        conditions.push(format!("offer_until >= ${}", params.len()));
+    }
+    if let Some(offer_until_to) = filter.offer_until_to {
+        params.push(Box::new(offer_until_to));
+        conditions.push(format!("offer_until <= ${}", params.len()));
+    }
+    if let Some(ref title) = filter.title {
+        params.push(Box::new(format!("%{}%", escape_like(title))));
+        conditions.push(format!("title ILIKE ${}", params.len()));
+    }
 
-        Ok(())
+    let count_query = format!("SELECT COUNT(*) FROM games WHERE {}", conditions.join(" AND "));
+    let filter_params = params.len();
+
+    let sort = filter.sort();
+    let (direction, comparison) = if sort.is_descending() { ("DESC", "<") } else { ("ASC", ">") };
+
+    if let Some(cursor) = cursor {
+        match cursor.key {
+            CursorKey::Price(price) => params.push(Box::new(price)),
+            CursorKey::Date(date) => params.push(Box::new(date)),
+            CursorKey::Title(ref title) => params.push(Box::new(title.clone())),
+        }
+        params.push(Box::new(cursor.store.clone()));
+        params.push(Box::new(cursor.id.clone()));
+        conditions.push(format!(
+            "({}, store, id) {comparison} (${}, ${}, ${})",
+            sort.key(),
+            params.len() - 2,
+            params.len() - 1,
+            params.len(),
+        ));
+    }
+
+    let limit = filter.limit.map(|limit| limit.clamp(1, MAX_PAGE_SIZE));
+    if let Some(limit) = limit {
+        params.push(Box::new(limit + 1));
+    }
+
+    let query = format!(
+        "SELECT *, {key} AS sort_key FROM games WHERE {} ORDER BY sort_key {direction}, store {direction}, id {direction}{}",
+        conditions.join(" AND "),
+        if limit.is_some() { format!(" LIMIT ${}", params.len()) } else { String::new() },
+        key = sort.key(),
+    );
+
+    GamesSql {
+        count_query,
+        query,
+        params,
+        filter_params,
+        limit,
     }
 }
 
+/// Escapes the wildcards of a `LIKE` pattern, so they match literally.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 fn row_to_game(row: &Row) -> Result<Game, tokio_postgres::Error> {
     let store: String = row.try_get("store")?;
     let game_type: String = row.try_get("game_type")?;
+    let offer_kind: String = row.try_get("offer_kind")?;
+    let price_minor: Option<i64> = row.try_get("original_price_minor")?;
+    let price_currency: Option<String> = row.try_get("original_price_currency")?;
 
     Ok(Game {
         id: row.try_get("id")?,
@@ -183,8 +427,255 @@ fn row_to_game(row: &Row) -> Result<Game, tokio_postgres::Error> {
         title: row.try_get("title")?,
         identifier: row.try_get("identifier")?,
         url: row.try_get("url")?,
-        original_price: row.try_get("original_price")?,
+        original_price: match (price_minor, price_currency) {
+            (Some(amount_minor), Some(currency)) => Some(Price::new(amount_minor, &currency)),
+            _ => None,
+        },
+        offer_from: row.try_get("offer_from")?,
         offer_until: row.try_get("offer_until")?,
         game_type: GameType::from(game_type),
+        redeem_on: row.try_get::<_, Option<String>>("redeem_on")?.map(Launcher::from),
+        offer_kind: OfferKind::from(offer_kind),
+        image_url: row.try_get("image_url")?,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Days, NaiveDate, Utc};
+    use utils::model::PostKind;
+    use super::*;
+
+    /// Creates an empty, migrated database next to the one `TEST_DATABASE_URL` points to. Tests
+    /// that need Postgres are skipped when it isn't set, except on CI where they must run.
+    async fn test_database(name: &str) -> Option<Database> {
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            assert!(std::env::var_os("CI").is_none(), "TEST_DATABASE_URL must be set on CI");
+            eprintln!("TEST_DATABASE_URL is not set, skipping");
+            return None;
+        };
+        let mut config = url.parse::<tokio_postgres::Config>().unwrap();
+
+        let (admin, connection) = config.connect(postgres::NoTls).await.unwrap();
+        tokio::spawn(connection);
+        let database = format!("test_{name}");
+        admin.batch_execute(&format!("DROP DATABASE IF EXISTS {database} WITH (FORCE);")).await.unwrap();
+        admin.batch_execute(&format!("CREATE DATABASE {database};")).await.unwrap();
+
+        config.dbname(&database);
+        let manager = Manager::from_config(config, postgres::NoTls, ManagerConfig { recycling_method: RecyclingMethod::Verified });
+        let pool = Pool::builder(manager).max_size(1).runtime(Runtime::Tokio1).build().unwrap();
+        migrations::run(&mut pool.get().await.unwrap()).await.unwrap();
+
+        Some(Database { pool })
+    }
+
+    fn make_game(id: &str, price: Price, offer_kind: OfferKind, offer_from: Option<NaiveDate>) -> Game {
+        Game {
+            id: id.to_string(),
+            store: GameStore::Steam,
+            title: format!("Game {id}"),
+            identifier: format!("Game_{id}"),
+            url: format!("https://icudev.xyz/{id}"),
+            original_price: Some(price),
+            offer_from,
+            offer_until: Utc::now().date_naive() + Days::new(14),
+            game_type: GameType::Game,
+            redeem_on: None,
+            offer_kind,
+            image_url: None,
+        }
+    }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("100% Orange_Juice \\o/"), "100\\% Orange\\_Juice \\\\o/");
+    }
+
+    #[test]
+    fn test_games_sql_filters() {
+        let filter = GamesQuery {
+            currency: Some("USD".to_string()),
+            min_price: Some(500),
+            store: Some("steam".to_string()),
+            game_type: Some("dlc".to_string()),
+            created_from: NaiveDate::from_ymd_opt(2025, 6, 1),
+            title: Some("100%".to_string()),
+            ..Default::default()
+        };
+        let sql = build_games_sql(&filter, GamesStatus::Upcoming, None);
+
+        assert_eq!(
+            sql.count_query,
+            "SELECT COUNT(*) FROM games WHERE offer_from > CURRENT_DATE AND original_price_currency = $1 \
+            AND original_price_minor >= $2 AND store = $3 AND game_type = $4 AND created_at >= $5 AND title ILIKE $6",
+        );
+        assert_eq!(
+            sql.query,
+            "SELECT *, created_at AS sort_key FROM games WHERE offer_from > CURRENT_DATE AND original_price_currency = $1 \
+            AND original_price_minor >= $2 AND store = $3 AND game_type = $4 AND created_at >= $5 AND title ILIKE $6 \
+            ORDER BY sort_key DESC, store DESC, id DESC",
+        );
+        assert_eq!(format!("{:?}", sql.params), r#"["USD", 500, "Steam", "DLC", 2025-06-01, "%100\\%%"]"#);
+        assert_eq!(sql.filter_params, 6);
+        assert_eq!(sql.limit, None);
+    }
+
+    #[test]
+    fn test_games_sql_page() {
+        let filter = GamesQuery {
+            store: Some("GOG".to_string()),
+            sort: Some(GamesSort::PriceAsc),
+            limit: Some(20),
+            ..Default::default()
+        };
+        let cursor = Cursor {
+            sort: GamesSort::PriceAsc,
+            key: CursorKey::Price(1999),
+            store: "GOG".to_string(),
+            id: "123".to_string(),
+        };
+        let sql = build_games_sql(&filter, GamesStatus::Active, Some(&cursor));
+
+        // The total counts all pages, so neither the cursor nor the limit are part of it
+        assert_eq!(
+            sql.count_query,
+            "SELECT COUNT(*) FROM games WHERE offer_until > CURRENT_DATE AND (offer_from IS NULL OR offer_from <= CURRENT_DATE) \
+            AND store = $1",
+        );
+        assert_eq!(
+            sql.query,
+            "SELECT *, COALESCE(original_price_minor, 9223372036854775807) AS sort_key FROM games \
+            WHERE offer_until > CURRENT_DATE AND (offer_from IS NULL OR offer_from <= CURRENT_DATE) AND store = $1 \
+            AND (COALESCE(original_price_minor, 9223372036854775807), store, id) > ($2, $3, $4) \
+            ORDER BY sort_key ASC, store ASC, id ASC LIMIT $5",
+        );
+        assert_eq!(format!("{:?}", sql.params), r#"["GOG", 1999, "GOG", "123", 21]"#);
+        assert_eq!(sql.filter_params, 1);
+        assert_eq!(sql.limit, Some(20));
+    }
+
+    #[test]
+    fn test_games_sql_limit() {
+        let filter = |limit| GamesQuery {
+            sort: Some(GamesSort::TitleDesc),
+            limit: Some(limit),
+            ..Default::default()
+        };
+        let cursor = Cursor {
+            sort: GamesSort::TitleDesc,
+            key: CursorKey::Title("The Game".to_string()),
+            store: "Steam".to_string(),
+            id: "1".to_string(),
+        };
+
+        let sql = build_games_sql(&filter(0), GamesStatus::Any, Some(&cursor));
+        assert!(sql.query.ends_with("WHERE TRUE AND (title, store, id) < ($1, $2, $3) ORDER BY sort_key DESC, store DESC, id DESC LIMIT $4"));
+        assert_eq!(format!("{:?}", sql.params), r#"["The Game", "Steam", "1", 2]"#);
+        assert_eq!(sql.limit, Some(1));
+
+        let sql = build_games_sql(&filter(10_000), GamesStatus::Any, None);
+        assert_eq!(format!("{:?}", sql.params), format!("[{}]", MAX_PAGE_SIZE + 1));
+        assert_eq!(sql.limit, Some(MAX_PAGE_SIZE));
+    }
+
+    #[test]
+    fn test_parse_pool_size() {
+        assert_eq!(parse_pool_size(None), Ok(DEFAULT_POOL_SIZE));
+        assert_eq!(parse_pool_size(Some("")), Ok(DEFAULT_POOL_SIZE));
+        assert_eq!(parse_pool_size(Some("4")), Ok(4));
+        assert!(parse_pool_size(Some("0")).is_err());
+        assert!(parse_pool_size(Some("-1")).is_err());
+        assert!(parse_pool_size(Some("many")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_remove_game() {
+        let Some(db) = test_database("remove_game").await else {
+            return;
+        };
+        let game = make_game("1", Price::new(1999, "USD"), OfferKind::Keep, None);
+        let partial_game = PartialGame {
+            id: game.id.clone(),
+            store: game.store.clone(),
+        };
+        let post = PostedPlatform {
+            game_id: game.id.clone(),
+            game_store: game.store.clone(),
+            platform: "Discord".to_string(),
+            kind: PostKind::Free,
+        };
+
+        assert!(db.add_game(&game).await.unwrap());
+        assert!(db.add_posted(&post).await.unwrap());
+
+        assert!(db.remove_game(&partial_game).await.unwrap());
+        assert!(!db.game_exists(&partial_game).await.unwrap());
+        assert!(!db.is_posted(&post).await.unwrap());
+        assert!(!db.remove_game(&partial_game).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_games_pages() {
+        let Some(db) = test_database("games_pages").await else {
+            return;
+        };
+        for i in 1..=5 {
+            let game = make_game(&i.to_string(), Price::new(i * 100, "USD"), OfferKind::Keep, None);
+            assert!(db.add_game(&game).await.unwrap());
+        }
+
+        let mut filter = GamesQuery {
+            sort: Some(GamesSort::PriceDesc),
+            limit: Some(2),
+            ..Default::default()
+        };
+        let mut pages = vec![];
+        loop {
+            let cursor = filter.validate().unwrap();
+            let page = db.get_games(&filter, GamesStatus::Active, cursor.as_ref()).await.unwrap();
+            assert_eq!(page.total, 5);
+            pages.push(page.games.iter().map(|game| game.id.clone()).collect::<Vec<_>>());
+
+            match page.next_cursor {
+                Some(next_cursor) => filter.cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+        assert_eq!(pages, vec![vec!["5", "4"], vec!["3", "2"], vec!["1"]]);
+
+        // Continuing after the second most expensive game makes no sense when sorting the other way
+        let first_page = db.get_games(&GamesQuery { cursor: None, ..filter }, GamesStatus::Active, None).await.unwrap();
+        let other_direction = GamesQuery {
+            sort: Some(GamesSort::PriceAsc),
+            cursor: first_page.next_cursor,
+            ..Default::default()
+        };
+        assert!(other_direction.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_given_away_value() {
+        let Some(db) = test_database("given_away_value").await else {
+            return;
+        };
+        let today = Utc::now().date_naive();
+
+        for game in [
+            make_game("1", Price::new(1999, "USD"), OfferKind::Keep, None),
+            make_game("2", Price::new(500, "EUR"), OfferKind::Keep, Some(today)),
+            // Neither games that can't be kept nor giveaways that haven't started are given away
+            make_game("3", Price::new(2999, "USD"), OfferKind::TemporaryPlay, None),
+            make_game("4", Price::new(999, "USD"), OfferKind::Keep, Some(today + Days::new(7))),
+        ] {
+            assert!(db.add_game(&game).await.unwrap());
+        }
+
+        let totals = db.get_given_away_value(&ValueQuery::default()).await.unwrap();
+        let totals = totals.iter()
+            .map(|total| (total.currency.as_str(), total.amount_minor, total.games))
+            .collect::<Vec<_>>();
+
+        assert_eq!(totals, vec![("USD", 1999, 1), ("EUR", 500, 1)]);
+    }
+}
@@ -1,5 +1,7 @@
 mod database;
+mod migrations;
 mod paths;
+mod query;
 
 use actix_web::{App, HttpServer, web};
 use crate::database::Database;
@@ -14,6 +16,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Ok(db) => db,
         Err(e) => return Err(e),
     };
+
+    // Lets deployments migrate the database ahead of starting the new version
+    if std::env::args().any(|arg| arg == "--migrate-only") {
+        log::info!("Database is up to date.");
+        return Ok(());
+    }
+
     let data = web::Data::new(db);
 
     log::info!("Starting server on 0.0.0.0:{port}...");
@@ -23,11 +32,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .app_data(data.clone())
             .service(paths::index)
             .service(paths::get_free)
+            .service(paths::get_upcoming)
+            .service(paths::get_value)
             .service(paths::get_game)
             .service(paths::post_game)
             .service(paths::delete_game)
             .service(paths::get_posted)
             .service(paths::post_posted)
+            .service(
+                web::scope("/v2")
+                    .service(paths::v2::get_games)
+                    .service(paths::v2::get_free)
+                    .service(paths::v2::get_upcoming)
+                    .service(paths::v2::post_game)
+                    .service(paths::v2::get_game)
+                    .service(paths::v2::delete_game)
+                    .service(paths::v2::get_post)
+                    .service(paths::v2::post_post)
+            )
     })
         .bind(format!("0.0.0.0:{port}"))?
         .run()
@@ -0,0 +1,70 @@
+use tokio_postgres::Client;
+
+/// A schema change, applied once and in order of its version.
+pub(crate) struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// Migrations must never be changed once released, add a new one instead.
+pub(crate) const MIGRATIONS: [Migration; 2] = [
+    Migration {
+        version: 1,
+        name: "initial",
+        sql: include_str!("../migrations/0001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "offers_prices_and_artwork",
+        sql: include_str!("../migrations/0002_offers_prices_and_artwork.sql"),
+    },
+];
+
+/// Applies all migrations the database hasn't seen yet, each in its own transaction.
+pub(crate) async fn run(client: &mut Client) -> Result<(), tokio_postgres::Error> {
+    client.batch_execute(r#"CREATE TABLE IF NOT EXISTS public.schema_migrations
+        (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        );"#).await?;
+
+    for migration in &MIGRATIONS {
+        let transaction = client.transaction().await?;
+
+        // Another instance starting at the same time waits here until we're done
+        transaction.batch_execute("LOCK TABLE public.schema_migrations IN EXCLUSIVE MODE;").await?;
+
+        let applied = transaction
+            .query_opt("SELECT 1 FROM public.schema_migrations WHERE version = $1;", &[&migration.version])
+            .await?
+            .is_some();
+        if applied {
+            continue;
+        }
+
+        log::info!("Applying migration {} ({})...", migration.version, migration.name);
+
+        transaction.batch_execute(migration.sql).await?;
+        transaction.execute(
+            "INSERT INTO public.schema_migrations (version, name) VALUES ($1, $2);",
+            &[&migration.version, &migration.name],
+        ).await?;
+        transaction.commit().await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_sequential() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as i32 + 1, "migration {} is out of order", migration.name);
+        }
+    }
+}
@@ -1,36 +1,51 @@
-use actix_web::{HttpResponse, Responder, get, post, web::{Data, Json}, delete, HttpRequest};
-use crate::database::Database;
+use actix_web::{HttpResponse, Responder, get, post, web::{Data, Json, Query}, delete, HttpRequest};
+use crate::database::{Database, GamesStatus};
+use crate::query::{Cursor, GamesQuery, ValueQuery};
 use utils::model::{Game, PartialGame, PostedPlatform};
 
-fn check_token(req: &HttpRequest) -> Result<(), HttpResponse> {
+pub(crate) mod v2;
+
+fn check_token(req: &HttpRequest) -> Result<(), Box<HttpResponse>> {
     let token = std::env::var("INTERNAL_API_AUTH_TOKEN").unwrap();
     
     let header = match req.headers().get("API-Token") {
         Some(header_value) => match header_value.to_str() {
             Ok(header) => header,
-            Err(_) => return Err(HttpResponse::BadRequest().body("Invalid API-Token header"))
+            Err(_) => return Err(Box::new(HttpResponse::BadRequest().body("Invalid API-Token header")))
         },
-        None => return Err(HttpResponse::Unauthorized().body("Missing API-Token header"))
+        None => return Err(Box::new(HttpResponse::Unauthorized().body("Missing API-Token header")))
     };
     
     if token.as_str() != header {
-        return Err(HttpResponse::Unauthorized().body("Invalid API-Token"));
+        return Err(Box::new(HttpResponse::Unauthorized().body("Invalid API-Token")));
     }
     
     Ok(())
 }
 
+fn parse_filter(filter: &GamesQuery) -> Result<Option<Cursor>, Box<HttpResponse>> {
+    filter.validate().map_err(|err| Box::new(HttpResponse::BadRequest().body(err)))
+}
 
 #[get("/")]
-pub(crate) async fn index(db: Data<Database>, req: HttpRequest) -> impl Responder {
+pub(crate) async fn index(filter: Query<GamesQuery>, db: Data<Database>, req: HttpRequest) -> impl Responder {
     log::debug!("GET /");
 
     if let Err(res) = check_token(&req) {
-        return res;
+        return *res;
+    }
+
+    // Only a plain list of games is returned here, which has no room for the next cursor
+    if filter.limit.is_some() || filter.cursor.is_some() {
+        return HttpResponse::BadRequest().body("Pagination is only supported by /v2/games");
+    }
+
+    if let Err(res) = parse_filter(&filter) {
+        return *res;
     }
 
-    match db.get_all_games().await {
-        Ok(games) => HttpResponse::Ok().json(games),
+    match db.get_games(&filter, GamesStatus::Any, None).await {
+        Ok(page) => HttpResponse::Ok().json(page.games),
         Err(_) => HttpResponse::InternalServerError().finish()
     }
 }
@@ -40,7 +55,7 @@ pub(crate) async fn get_free(db: Data<Database>, req: HttpRequest) -> impl Respo
     log::debug!("GET /free");
     
     if let Err(res) = check_token(&req) {
-        return res;
+        return *res;
     }
     
     let games = db.get_active_games().await.unwrap_or_default();
@@ -48,12 +63,42 @@ pub(crate) async fn get_free(db: Data<Database>, req: HttpRequest) -> impl Respo
     HttpResponse::Ok().json(games)
 }
 
+#[get("/upcoming")]
+pub(crate) async fn get_upcoming(db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("GET /upcoming");
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    let games = db.get_upcoming_games().await.unwrap_or_default();
+
+    HttpResponse::Ok().json(games)
+}
+
+#[get("/value")]
+pub(crate) async fn get_value(filter: Query<ValueQuery>, db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("GET /value");
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    match db.get_given_away_value(&filter).await {
+        Ok(totals) => HttpResponse::Ok().json(totals),
+        Err(err) => {
+            log::error!("GET /value failed: {err}");
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
 #[get("/game")]
 pub(crate) async fn get_game(game: Json<PartialGame>, db: Data<Database>, req: HttpRequest) -> impl Responder {
     log::debug!("GET /game");
 
     if let Err(res) = check_token(&req) {
-        return res;
+        return *res;
     }
 
     match db.game_exists(&game).await {
@@ -70,7 +115,7 @@ pub(crate) async fn delete_game(game: Json<PartialGame>, db: Data<Database>, req
     log::debug!("DELETE /game");
 
     if let Err(res) = check_token(&req) {
-        return res;
+        return *res;
     }
 
     match db.remove_game(&game).await {
@@ -87,7 +132,7 @@ pub(crate) async fn post_game(game: Json<Game>, db: Data<Database>, req: HttpReq
     log::debug!("POST /game");
 
     if let Err(res) = check_token(&req) {
-        return res;
+        return *res;
     }
 
     match db.add_game(&game).await {
@@ -104,7 +149,7 @@ pub(crate) async fn get_posted(posted_data: Json<PostedPlatform>, db: Data<Datab
     log::debug!("GET /posted");
 
     if let Err(res) = check_token(&req) {
-        return res;
+        return *res;
     }
 
     HttpResponse::Ok().body(db.is_posted(&posted_data).await.unwrap_or(true).to_string())
@@ -115,7 +160,7 @@ pub(crate) async fn post_posted(posted_data: Json<PostedPlatform>, db: Data<Data
     log::debug!("POST /posted");
 
     if let Err(res) = check_token(&req) {
-        return res;
+        return *res;
     }
 
     match db.add_posted(&posted_data).await {
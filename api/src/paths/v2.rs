@@ -0,0 +1,206 @@
+//! Versioned API, games and their posts are addressed by path instead of by request bodies.
+//!
+//! - `GET /v2/games`, `GET /v2/games/free`, `GET /v2/games/upcoming`, filtered and paginated by
+//!   [`GamesQuery`], respond with a [`GamesPage`](utils::model::GamesPage)
+//! - `POST /v2/games`, responds with `409 Conflict` if the game already exists
+//! - `GET`/`DELETE /v2/games/{store}/{id}`, respond with `404 Not Found` for unknown games
+//! - `GET`/`POST /v2/games/{store}/{id}/posts/{platform}?kind=Free|Upcoming`, posting responds
+//!   with `409 Conflict` if the game was already posted on the platform
+
+use actix_web::{HttpResponse, Responder, get, post, web::{Data, Json, Path, Query}, delete, HttpRequest};
+use crate::database::{Database, GamesStatus};
+use crate::paths::{check_token, parse_filter};
+use crate::query::{GamesQuery, PostQuery};
+use utils::model::{Game, GameStore, PartialGame, PostedPlatform};
+
+fn parse_game(store: &str, id: &str) -> Result<PartialGame, Box<HttpResponse>> {
+    match GameStore::from(store.to_string()) {
+        GameStore::Unknown => Err(Box::new(HttpResponse::BadRequest().body(format!("Unknown store {store}")))),
+        store => Ok(PartialGame { id: id.to_string(), store }),
+    }
+}
+
+async fn list_games(filter: &GamesQuery, status: GamesStatus, db: &Database, route: &str) -> HttpResponse {
+    let cursor = match parse_filter(filter) {
+        Ok(cursor) => cursor,
+        Err(res) => return *res,
+    };
+
+    match db.get_games(filter, status, cursor.as_ref()).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => {
+            log::error!("GET {route} failed: {err}");
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+#[get("/games")]
+pub(crate) async fn get_games(filter: Query<GamesQuery>, db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("GET /v2/games");
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    list_games(&filter, GamesStatus::Any, &db, "/v2/games").await
+}
+
+#[get("/games/free")]
+pub(crate) async fn get_free(filter: Query<GamesQuery>, db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("GET /v2/games/free");
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    list_games(&filter, GamesStatus::Active, &db, "/v2/games/free").await
+}
+
+#[get("/games/upcoming")]
+pub(crate) async fn get_upcoming(filter: Query<GamesQuery>, db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("GET /v2/games/upcoming");
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    list_games(&filter, GamesStatus::Upcoming, &db, "/v2/games/upcoming").await
+}
+
+#[post("/games")]
+pub(crate) async fn post_game(game: Json<Game>, db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("POST /v2/games");
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    if let GameStore::Unknown = game.store {
+        return HttpResponse::BadRequest().body("Unknown store");
+    }
+
+    match db.add_game(&game).await {
+        Ok(true) => HttpResponse::Created().json(game.into_inner()),
+        Ok(false) => HttpResponse::Conflict().body("Game already exists"),
+        Err(err) => {
+            log::error!("POST /v2/games failed: {err}");
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+#[get("/games/{store}/{id}")]
+pub(crate) async fn get_game(path: Path<(String, String)>, db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("GET /v2/games/{}/{}", path.0, path.1);
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    let game = match parse_game(&path.0, &path.1) {
+        Ok(game) => game,
+        Err(res) => return *res,
+    };
+
+    match db.get_game(&game).await {
+        Ok(Some(game)) => HttpResponse::Ok().json(game),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("GET /v2/games/{}/{} failed: {err}", path.0, path.1);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+#[delete("/games/{store}/{id}")]
+pub(crate) async fn delete_game(path: Path<(String, String)>, db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("DELETE /v2/games/{}/{}", path.0, path.1);
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    let game = match parse_game(&path.0, &path.1) {
+        Ok(game) => game,
+        Err(res) => return *res,
+    };
+
+    match db.remove_game(&game).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("DELETE /v2/games/{}/{} failed: {err}", path.0, path.1);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+#[get("/games/{store}/{id}/posts/{platform}")]
+pub(crate) async fn get_post(path: Path<(String, String, String)>, query: Query<PostQuery>, db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("GET /v2/games/{}/{}/posts/{}", path.0, path.1, path.2);
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    let game = match parse_game(&path.0, &path.1) {
+        Ok(game) => game,
+        Err(res) => return *res,
+    };
+    let posted = PostedPlatform {
+        platform: path.2.clone(),
+        game_id: game.id,
+        game_store: game.store,
+        kind: query.into_inner().kind,
+    };
+
+    match db.is_posted(&posted).await {
+        Ok(true) => HttpResponse::Ok().json(posted),
+        Ok(false) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            log::error!("GET /v2/games/{}/{}/posts/{} failed: {err}", path.0, path.1, path.2);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+#[post("/games/{store}/{id}/posts/{platform}")]
+pub(crate) async fn post_post(path: Path<(String, String, String)>, query: Query<PostQuery>, db: Data<Database>, req: HttpRequest) -> impl Responder {
+    log::debug!("POST /v2/games/{}/{}/posts/{}", path.0, path.1, path.2);
+
+    if let Err(res) = check_token(&req) {
+        return *res;
+    }
+
+    let game = match parse_game(&path.0, &path.1) {
+        Ok(game) => game,
+        Err(res) => return *res,
+    };
+
+    // Posts can only be recorded for games we know, unlike on the unversioned endpoint
+    match db.game_exists(&game).await {
+        Ok(true) => {},
+        Ok(false) => return HttpResponse::NotFound().body("Game not found"),
+        Err(err) => {
+            log::error!("POST /v2/games/{}/{}/posts/{} failed: {err}", path.0, path.1, path.2);
+            return HttpResponse::InternalServerError().finish();
+        },
+    }
+
+    let posted = PostedPlatform {
+        platform: path.2.clone(),
+        game_id: game.id,
+        game_store: game.store,
+        kind: query.into_inner().kind,
+    };
+
+    match db.add_posted(&posted).await {
+        Ok(true) => HttpResponse::Created().json(posted),
+        Ok(false) => HttpResponse::Conflict().body("Game is already posted on this platform"),
+        Err(err) => {
+            log::error!("POST /v2/games/{}/{}/posts/{} failed: {err}", path.0, path.1, path.2);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
@@ -0,0 +1,217 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utils::model::{GameStore, GameType, PostKind};
+
+/// Query parameters of the game listing, all dates are inclusive.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct GamesQuery {
+    pub currency: Option<String>,
+    pub min_price: Option<i64>,
+    pub max_price: Option<i64>,
+    pub store: Option<String>,
+    pub game_type: Option<String>,
+    pub created_from: Option<NaiveDate>,
+    pub created_to: Option<NaiveDate>,
+    pub offer_until_from: Option<NaiveDate>,
+    pub offer_until_to: Option<NaiveDate>,
+    /// Case-insensitive substring of the title
+    pub title: Option<String>,
+    pub sort: Option<GamesSort>,
+    /// Without a limit every matching game is returned at once
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+impl GamesQuery {
+    pub fn sort(&self) -> GamesSort {
+        self.sort.unwrap_or(GamesSort::CreatedDesc)
+    }
+
+    pub fn cursor(&self) -> Result<Option<Cursor>, String> {
+        self.cursor.as_deref()
+            .map(|cursor| Cursor::decode(cursor, self.sort()))
+            .transpose()
+    }
+
+    /// Rejects unknown stores and game types, which would never match a game, and decodes the cursor.
+    pub fn validate(&self) -> Result<Option<Cursor>, String> {
+        if let Some(ref store) = self.store
+            && GameStore::from(store.clone()) == GameStore::Unknown
+        {
+            return Err(format!("Unknown store {store}"));
+        }
+        if let Some(ref game_type) = self.game_type
+            && matches!(GameType::from(game_type.clone()), GameType::Unknown)
+        {
+            return Err(format!("Unknown game type {game_type}"));
+        }
+
+        self.cursor()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GamesSort {
+    PriceAsc,
+    PriceDesc,
+    CreatedAsc,
+    CreatedDesc,
+    OfferUntilAsc,
+    OfferUntilDesc,
+    TitleAsc,
+    TitleDesc,
+}
+
+impl GamesSort {
+    /// Games without a price are sorted last either way, which is why the key is never NULL.
+    pub fn key(&self) -> &'static str {
+        match self {
+            GamesSort::PriceAsc => "COALESCE(original_price_minor, 9223372036854775807)",
+            GamesSort::PriceDesc => "COALESCE(original_price_minor, -1)",
+            GamesSort::CreatedAsc | GamesSort::CreatedDesc => "created_at",
+            GamesSort::OfferUntilAsc | GamesSort::OfferUntilDesc => "offer_until",
+            GamesSort::TitleAsc | GamesSort::TitleDesc => "title",
+        }
+    }
+
+    pub fn is_descending(&self) -> bool {
+        matches!(self, GamesSort::PriceDesc | GamesSort::CreatedDesc | GamesSort::OfferUntilDesc | GamesSort::TitleDesc)
+    }
+}
+
+/// Position after the last game of a page. Games with the same sort key are ordered by store and
+/// id, so pages neither skip nor repeat games.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Cursor {
+    pub sort: GamesSort,
+    pub key: CursorKey,
+    pub store: String,
+    pub id: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum CursorKey {
+    Price(i64),
+    Date(NaiveDate),
+    Title(String),
+}
+
+#[derive(Deserialize, Serialize)]
+struct RawCursor {
+    sort: GamesSort,
+    key: Value,
+    store: String,
+    id: String,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let key = match self.key {
+            CursorKey::Price(price) => Value::from(price),
+            CursorKey::Date(date) => Value::from(date.to_string()),
+            CursorKey::Title(ref title) => Value::from(title.as_str()),
+        };
+        let raw = RawCursor {
+            sort: self.sort,
+            key,
+            store: self.store.clone(),
+            id: self.id.clone(),
+        };
+
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&raw).expect("cursors are always serializable"))
+    }
+
+    /// Cursors are only valid for the sort order of the page they came from, a cursor of the same
+    /// field in the other direction would continue at the wrong end.
+    pub fn decode(cursor: &str, sort: GamesSort) -> Result<Self, String> {
+        let raw = URL_SAFE_NO_PAD.decode(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice::<RawCursor>(&json).ok())
+            .ok_or("Invalid cursor")?;
+
+        if raw.sort != sort {
+            return Err("Cursor doesn't match the sort order".to_string());
+        }
+
+        let key = match (sort, &raw.key) {
+            (GamesSort::PriceAsc | GamesSort::PriceDesc, Value::Number(price)) => {
+                price.as_i64().map(CursorKey::Price)
+            },
+            (GamesSort::CreatedAsc | GamesSort::CreatedDesc | GamesSort::OfferUntilAsc | GamesSort::OfferUntilDesc, Value::String(date)) => {
+                date.parse().ok().map(CursorKey::Date)
+            },
+            (GamesSort::TitleAsc | GamesSort::TitleDesc, Value::String(title)) => Some(CursorKey::Title(title.clone())),
+            _ => None,
+        };
+
+        Ok(Self {
+            sort,
+            key: key.ok_or("Cursor doesn't match the sort order")?,
+            store: raw.store,
+            id: raw.id,
+        })
+    }
+}
+
+/// Query parameters of the given away value summary, both dates are inclusive.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ValueQuery {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+}
+
+/// Query parameters of a single post of a game, posts of free and upcoming games are separate.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct PostQuery {
+    #[serde(default)]
+    pub kind: PostKind,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let cursor = Cursor {
+            sort: GamesSort::OfferUntilAsc,
+            key: CursorKey::Date(NaiveDate::from_ymd_opt(2025, 6, 15).unwrap()),
+            store: "Steam".to_string(),
+            id: "123".to_string(),
+        };
+
+        assert_eq!(Cursor::decode(&cursor.encode(), GamesSort::OfferUntilAsc), Ok(cursor));
+    }
+
+    #[test]
+    fn test_validate_filters() {
+        let query = |store: &str, game_type: &str| GamesQuery {
+            store: Some(store.to_string()),
+            game_type: Some(game_type.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(query("steam", "dlc").validate(), Ok(None));
+        assert_eq!(query("Origin", "dlc").validate(), Err("Unknown store Origin".to_string()));
+        assert_eq!(query("GOG", "Mod").validate(), Err("Unknown game type Mod".to_string()));
+    }
+
+    #[test]
+    fn test_cursor_rejects_other_sort() {
+        let cursor = Cursor {
+            sort: GamesSort::PriceAsc,
+            key: CursorKey::Price(1999),
+            store: "Steam".to_string(),
+            id: "123".to_string(),
+        }.encode();
+
+        assert!(Cursor::decode(&cursor, GamesSort::PriceAsc).is_ok());
+        assert!(Cursor::decode(&cursor, GamesSort::PriceDesc).is_err());
+        assert!(Cursor::decode(&cursor, GamesSort::TitleAsc).is_err());
+        assert!(Cursor::decode("not a cursor", GamesSort::PriceAsc).is_err());
+    }
+}
@@ -1,8 +1,19 @@
 use utils::internal_api::{wait_for_internal_api, InternalApi};
-use utils::model::{Game, PostedPlatform};
-use crate::platforms::Platform;
+use utils::model::{Game, PostKind, PostedPlatform};
+use crate::media::ImageDownloader;
+use crate::platforms::{render_post, Platform};
+use crate::platforms::bluesky::BlueskyPlatform;
+use crate::platforms::discord::DiscordPlatform;
+use crate::platforms::email::EmailPlatform;
+use crate::platforms::mastodon::MastodonPlatform;
+use crate::platforms::matrix::MatrixPlatform;
+use crate::platforms::push::{GotifyPlatform, NtfyPlatform};
+use crate::platforms::reddit::RedditPlatform;
+use crate::platforms::telegram::TelegramPlatform;
 use crate::platforms::twitter::TwitterPlatform;
+use crate::platforms::webhook::WebhookPlatform;
 
+mod media;
 mod platforms;
 
 type Error = Box<dyn std::error::Error>;
@@ -13,20 +24,51 @@ const INTERVAL: u64 = 600;
 async fn main() -> Result<(), Error> {
     utils::logging::setup_logger()?;
 
-    let platforms: Vec<Box<dyn Platform>> = vec![
+    let mut platforms: Vec<Box<dyn Platform>> = vec![
         Box::new(TwitterPlatform::new()?)
     ];
+    if std::env::var("MASTODON_INSTANCE_URL").is_ok_and(|url| !url.is_empty()) {
+        platforms.push(Box::new(MastodonPlatform::new()?));
+    }
+    if std::env::var("BLUESKY_IDENTIFIER").is_ok_and(|identifier| !identifier.is_empty()) {
+        platforms.push(Box::new(BlueskyPlatform::new()?));
+    }
+    if ["DISCORD_WEBHOOK_URLS", "DISCORD_BOT_TOKEN"].iter().any(|key| std::env::var(key).is_ok_and(|value| !value.is_empty())) {
+        platforms.push(Box::new(DiscordPlatform::new()?));
+    }
+    if std::env::var("TELEGRAM_BOT_TOKEN").is_ok_and(|token| !token.is_empty()) {
+        platforms.push(Box::new(TelegramPlatform::new()?));
+    }
+    if std::env::var("MATRIX_ACCESS_TOKEN").is_ok_and(|token| !token.is_empty()) {
+        platforms.push(Box::new(MatrixPlatform::new()?));
+    }
+    if std::env::var("REDDIT_CLIENT_ID").is_ok_and(|client_id| !client_id.is_empty()) {
+        platforms.push(Box::new(RedditPlatform::new()?));
+    }
+    if std::env::var("SMTP_HOST").is_ok_and(|host| !host.is_empty()) {
+        platforms.push(Box::new(EmailPlatform::new()?));
+    }
+    if std::env::var("NTFY_TOPIC").is_ok_and(|topic| !topic.is_empty()) {
+        platforms.push(Box::new(NtfyPlatform::new()?));
+    }
+    if std::env::var("GOTIFY_SERVER_URL").is_ok_and(|url| !url.is_empty()) {
+        platforms.push(Box::new(GotifyPlatform::new()?));
+    }
+    if std::env::var("WEBHOOK_URLS").is_ok_and(|urls| !urls.is_empty()) {
+        platforms.push(Box::new(WebhookPlatform::new()?));
+    }
 
     let api_url = std::env::var("INTERNAL_API_URL")?;
     let api_token = std::env::var("INTERNAL_API_AUTH_TOKEN")?;
     let internal_api = InternalApi::new(api_url, api_token);
+    let images = ImageDownloader::new();
 
-    main_loop(&internal_api, &platforms).await?;
+    main_loop(&internal_api, &platforms, &images).await?;
 
     Ok(())
 }
 
-async fn main_loop(internal_api: &InternalApi, platforms: &Vec<Box<dyn Platform>>) -> Result<(), Error> {
+async fn main_loop(internal_api: &InternalApi, platforms: &Vec<Box<dyn Platform>>, images: &ImageDownloader) -> Result<(), Error> {
     if let Err(e) = wait_for_internal_api(internal_api).await {
         return Err(format!("Error while connecting to internal API: {e}").into());
     }
@@ -34,24 +76,39 @@ async fn main_loop(internal_api: &InternalApi, platforms: &Vec<Box<dyn Platform>
     loop {
         let games = internal_api.get_free_games().await?;
 
-        log::debug!("Found {} free games, dispatching.", games.len());
+        log::debug!("Found {} free games.", games.len());
+
+        let upcoming_games = internal_api.get_upcoming_games().await?;
+
+        log::debug!("Found {} upcoming games, dispatching.", upcoming_games.len());
 
-        dispatch_games(internal_api, platforms, &games).await?;
+        let games = games.into_iter()
+            .map(|game| (game, PostKind::Free))
+            .chain(upcoming_games.into_iter().map(|game| (game, PostKind::Upcoming)))
+            .collect::<Vec<_>>();
+
+        dispatch_games(internal_api, platforms, images, &games).await?;
 
         tokio::time::sleep(std::time::Duration::from_secs(INTERVAL)).await;
     }
 }
 
-async fn dispatch_games(internal_api: &InternalApi, platforms: &Vec<Box<dyn Platform>>, games: &Vec<Game>) -> Result<(), Error> {
-    for game in games {
+async fn dispatch_games(internal_api: &InternalApi, platforms: &Vec<Box<dyn Platform>>, images: &ImageDownloader, games: &Vec<(Game, PostKind)>) -> Result<(), Error> {
+    // Games for batched platforms are collected over the whole cycle and posted at the end
+    let mut batches = platforms.iter().map(|_| vec![]).collect::<Vec<_>>();
+
+    for (game, kind) in games {
         let game_id = game.id.clone();
         let game_store = game.store.clone();
+        // Rendered on demand, so games that are posted everywhere don't download their image again
+        let mut rendered = None;
 
-        for platform in platforms {
+        for (platform, batch) in platforms.iter().zip(batches.iter_mut()) {
             let posted = PostedPlatform {
                 game_id: game_id.clone(),
                 game_store: game_store.clone(),
-                platform: platform.name()
+                platform: platform.name(),
+                kind: kind.clone(),
             };
 
             if internal_api.is_posted(&posted).await? {
@@ -59,10 +116,20 @@ async fn dispatch_games(internal_api: &InternalApi, platforms: &Vec<Box<dyn Plat
                 continue;
             }
 
+            let post = match rendered {
+                Some(ref post) => post,
+                None => rendered.insert(render_post(game, kind, images).await),
+            };
+
+            if platform.is_batched() {
+                batch.push((posted, post.clone()));
+                continue;
+            }
+
             log::debug!("Posting game {game_id}");
-            match platform.post_game(game).await {
+            match platform.post(post).await {
                 Ok(_) => {
-                    let _ = internal_api.post_posted(&posted).await?;
+                    internal_api.post_posted(&posted).await?;
                     log::info!("Posted game \"{}\" to platform: {}", game.id, platform.name());
                 },
                 Err(e) => {
@@ -74,5 +141,26 @@ async fn dispatch_games(internal_api: &InternalApi, platforms: &Vec<Box<dyn Plat
         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
     }
 
+    for (platform, batch) in platforms.iter().zip(batches) {
+        if batch.is_empty() {
+            continue;
+        }
+
+        let (posted, posts): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+
+        log::debug!("Posting {} games as batch", posts.len());
+        match platform.post_batch(&posts).await {
+            Ok(_) => {
+                for posted in &posted {
+                    internal_api.post_posted(posted).await?;
+                }
+                log::info!("Posted {} games to platform: {}", posts.len(), platform.name());
+            },
+            Err(e) => {
+                log::error!("Failed to post {} games to platform {}: {}", posts.len(), platform.name(), e);
+            },
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,133 @@
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use reqwest::Client;
+use reqwest::header::CONTENT_TYPE;
+
+// Twitter rejects images above 5 MB, which is also the lowest limit of the platforms we post to
+const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024;
+// Games usually get posted within a few loops, so only the latest images are worth keeping
+const IMAGE_CACHE_SIZE: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub data: Vec<u8>,
+    pub content_type: String,
+}
+
+/// Downloads store artwork for posts, shared between all platforms so every image is only
+/// fetched once.
+pub struct ImageDownloader {
+    http: Client,
+    cache: Mutex<ImageCache>,
+}
+
+impl ImageDownloader {
+    pub fn new() -> Self {
+        Self {
+            http: Client::new(),
+            cache: Mutex::new(ImageCache::new(IMAGE_CACHE_SIZE)),
+        }
+    }
+
+    pub async fn get(&self, url: &str) -> Result<Arc<Image>, Box<dyn Error>> {
+        if let Some(image) = self.cache.lock().unwrap().get(url) {
+            return Ok(image);
+        }
+
+        let image = Arc::new(self.download(url).await?);
+        self.cache.lock().unwrap().insert(url.to_string(), image.clone());
+
+        Ok(image)
+    }
+
+    async fn download(&self, url: &str) -> Result<Image, Box<dyn Error>> {
+        let mut res = self.http.get(url)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let content_type = res.headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if !content_type.starts_with("image/") {
+            return Err(format!("{url} is not an image but {content_type}").into());
+        }
+
+        if res.content_length().is_some_and(|length| length as usize > MAX_IMAGE_SIZE) {
+            return Err(format!("{url} is larger than {MAX_IMAGE_SIZE} bytes").into());
+        }
+
+        // The content length is optional, so the limit has to be enforced while reading as well
+        let mut data = vec![];
+        while let Some(chunk) = res.chunk().await? {
+            if data.len() + chunk.len() > MAX_IMAGE_SIZE {
+                return Err(format!("{url} is larger than {MAX_IMAGE_SIZE} bytes").into());
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        Ok(Image {
+            data,
+            content_type,
+        })
+    }
+}
+
+struct ImageCache {
+    capacity: usize,
+    images: HashMap<String, Arc<Image>>,
+    order: VecDeque<String>,
+}
+
+impl ImageCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            images: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&self, url: &str) -> Option<Arc<Image>> {
+        self.images.get(url).cloned()
+    }
+
+    fn insert(&mut self, url: String, image: Arc<Image>) {
+        if self.images.insert(url.clone(), image).is_some() {
+            return;
+        }
+
+        self.order.push_back(url);
+        if self.order.len() > self.capacity && let Some(oldest) = self.order.pop_front() {
+            self.images.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(data: &[u8]) -> Arc<Image> {
+        Arc::new(Image {
+            data: data.to_vec(),
+            content_type: "image/png".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest() {
+        let mut cache = ImageCache::new(2);
+        cache.insert("a".to_string(), image(b"a"));
+        cache.insert("b".to_string(), image(b"b"));
+        cache.insert("a".to_string(), image(b"a"));
+        cache.insert("c".to_string(), image(b"c"));
+
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.get("b").unwrap().data, b"b");
+        assert_eq!(cache.get("c").unwrap().data, b"c");
+    }
+}
@@ -0,0 +1,353 @@
+mod model;
+
+use std::env;
+use std::error::Error;
+use chrono::{SecondsFormat, Utc};
+use reqwest::{Client, Response};
+use reqwest::header::CONTENT_TYPE;
+use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::platforms::{shorten_text, Platform, Post, PostImage};
+
+const BLUESKY_DEFAULT_PDS_URL: &str = "https://bsky.social";
+const BLUESKY_MAX_GRAPHEMES: usize = 300;
+// Blobs above this size are rejected by the PDS, the card is posted without a thumbnail then
+const BLUESKY_MAX_THUMB_SIZE: usize = 1_000_000;
+
+pub struct BlueskyPlatform {
+    http: Client,
+    pds_url: String,
+    identifier: String,
+    app_password: String,
+    session: Mutex<Option<model::Session>>,
+}
+
+impl BlueskyPlatform {
+    pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        let pds_url = env::var("BLUESKY_PDS_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or(BLUESKY_DEFAULT_PDS_URL.to_string());
+
+        Ok(Self::with_pds(
+            pds_url,
+            env::var("BLUESKY_IDENTIFIER")?,
+            env::var("BLUESKY_APP_PASSWORD")?,
+        ))
+    }
+
+    pub(crate) fn with_pds(pds_url: String, identifier: String, app_password: String) -> Self {
+        Self {
+            http: Client::new(),
+            pds_url: pds_url.trim_end_matches('/').to_string(),
+            identifier,
+            app_password,
+            session: Mutex::new(None),
+        }
+    }
+
+    async fn get_session(&self, renew: bool) -> Result<model::Session, Box<dyn Error>> {
+        let mut session = self.session.lock().await;
+        if let Some(ref session) = *session && !renew {
+            return Ok(session.clone());
+        }
+
+        let res = self.http.post(format!("{}/xrpc/com.atproto.server.createSession", self.pds_url))
+            .json(&model::CreateSessionRequest {
+                identifier: self.identifier.clone(),
+                password: self.app_password.clone(),
+            })
+            .send()
+            .await?;
+        let new_session = parse_response::<model::Session>(res).await?;
+
+        *session = Some(new_session.clone());
+        Ok(new_session)
+    }
+
+    async fn upload_thumb(&self, session: &model::Session, image: &PostImage) -> Result<serde_json::Value, Box<dyn Error>> {
+        if image.image.data.len() > BLUESKY_MAX_THUMB_SIZE {
+            return Err(format!("image is larger than {BLUESKY_MAX_THUMB_SIZE} bytes").into());
+        }
+
+        let res = self.http.post(format!("{}/xrpc/com.atproto.repo.uploadBlob", self.pds_url))
+            .bearer_auth(&session.access_jwt)
+            .header(CONTENT_TYPE, &image.image.content_type)
+            .body(image.image.data.clone())
+            .send()
+            .await?;
+
+        Ok(parse_response::<model::UploadBlobResponse>(res).await?.blob)
+    }
+
+    async fn create_post(&self, session: &model::Session, post: &Post) -> Result<(), Box<dyn Error>> {
+        let text = shorten_text(&post.text, BLUESKY_MAX_GRAPHEMES, |text| text.graphemes(true).count());
+
+        let thumb = match post.image {
+            Some(ref image) => match self.upload_thumb(session, image).await {
+                Ok(blob) => Some(blob),
+                Err(e) if is_expired(e.as_ref()) => return Err(e),
+                Err(e) => {
+                    log::error!("Couldn't upload image to Bluesky: {e}");
+                    None
+                },
+            },
+            None => None,
+        };
+
+        let record = model::PostRecord {
+            record_type: "app.bsky.feed.post".to_string(),
+            facets: make_facets(&text),
+            text,
+            created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            langs: vec!["en".to_string()],
+            embed: Some(model::ExternalEmbed {
+                embed_type: "app.bsky.embed.external".to_string(),
+                external: model::External {
+                    uri: post.game.url.clone(),
+                    title: post.game.title.clone(),
+                    description: String::new(),
+                    thumb,
+                },
+            }),
+        };
+
+        let res = self.http.post(format!("{}/xrpc/com.atproto.repo.createRecord", self.pds_url))
+            .bearer_auth(&session.access_jwt)
+            .json(&model::CreateRecordRequest {
+                repo: session.did.clone(),
+                collection: "app.bsky.feed.post".to_string(),
+                record,
+            })
+            .send()
+            .await?;
+        parse_response::<serde_json::Value>(res).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Platform for BlueskyPlatform {
+    fn name(&self) -> String {
+        String::from("Bluesky")
+    }
+
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        let session = self.get_session(false).await?;
+
+        match self.create_post(&session, post).await {
+            Err(e) if is_expired(e.as_ref()) => {},
+            res => return res,
+        }
+
+        // Access tokens only live for a few hours, so the session is renewed once
+        log::debug!("Bluesky session expired, creating a new one.");
+        let session = self.get_session(true).await?;
+        self.create_post(&session, post).await
+    }
+}
+
+async fn parse_response<T: DeserializeOwned>(res: Response) -> Result<T, Box<dyn Error>> {
+    if res.status().is_success() {
+        return Ok(res.json::<T>().await?);
+    }
+
+    let status = res.status();
+    match res.json::<model::XrpcError>().await {
+        Ok(error) => Err(error.into()),
+        Err(_) => Err(format!("request failed with status {status}").into()),
+    }
+}
+
+fn is_expired(error: &(dyn Error + 'static)) -> bool {
+    error.downcast_ref::<model::XrpcError>()
+        .is_some_and(|error| error.error == "ExpiredToken" || error.error == "InvalidToken")
+}
+
+/// Bluesky doesn't detect links and hashtags on its own, they have to be marked by their byte
+/// range in the text.
+fn make_facets(text: &str) -> Vec<model::Facet> {
+    let mut facets = vec![];
+    let mut offset = 0;
+
+    for word in text.split_inclusive(char::is_whitespace) {
+        let byte_start = offset;
+        offset += word.len();
+
+        let word = word.trim_end();
+        let feature = if word.starts_with("http://") || word.starts_with("https://") {
+            model::FacetFeature::Link { uri: word.to_string() }
+        } else if let Some(tag) = word.strip_prefix('#') {
+            let tag = tag.trim_end_matches(|c: char| c.is_ascii_punctuation() && c != '_');
+            if tag.is_empty() {
+                continue;
+            }
+            model::FacetFeature::Tag { tag: tag.to_string() }
+        } else {
+            continue;
+        };
+
+        let byte_end = byte_start + match feature {
+            model::FacetFeature::Link { ref uri } => uri.len(),
+            model::FacetFeature::Tag { ref tag } => tag.len() + 1,
+        };
+
+        facets.push(model::Facet {
+            index: model::ByteSlice {
+                byte_start,
+                byte_end,
+            },
+            features: vec![feature],
+        });
+    }
+
+    facets
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use crate::platforms::tests::make_test_post;
+    use super::*;
+
+    const TEXT: &str = "[ Game ] \"The Game\" is currently free on #Steam until 2025-06-15.\n\nhttps://icudev.xyz/the_game\n\n#FreeGames #The_Game #SteamDeals";
+
+    fn tag(byte_start: usize, tag: &str) -> model::Facet {
+        model::Facet {
+            index: model::ByteSlice {
+                byte_start,
+                byte_end: byte_start + tag.len() + 1,
+            },
+            features: vec![model::FacetFeature::Tag { tag: tag.to_string() }],
+        }
+    }
+
+    async fn mock_session(server: &MockServer, access_jwt: &str) {
+        Mock::given(method("POST"))
+            .and(path("/xrpc/com.atproto.server.createSession"))
+            .and(body_partial_json(json!({ "identifier": "freegames.bsky.social", "password": "app-password" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "accessJwt": access_jwt,
+                "refreshJwt": "refresh",
+                "handle": "freegames.bsky.social",
+                "did": "did:plc:freegames",
+            })))
+            .up_to_n_times(1)
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_facets() {
+        let text = "[ Game ] \"Café\" is free on #Steam.\n\nhttps://icudev.xyz/cafe\n\n#FreeGames #Café";
+
+        assert_eq!(make_facets(text), vec![
+            tag(28, "Steam"),
+            model::Facet {
+                index: model::ByteSlice {
+                    byte_start: 37,
+                    byte_end: 60,
+                },
+                features: vec![model::FacetFeature::Link { uri: "https://icudev.xyz/cafe".to_string() }],
+            },
+            tag(62, "FreeGames"),
+            tag(73, "Café"),
+        ]);
+        assert_eq!(&text[28..34], "#Steam");
+        assert_eq!(&text[73..], "#Café");
+    }
+
+    #[tokio::test]
+    async fn test_post() {
+        let server = MockServer::start().await;
+        mock_session(&server, "access").await;
+
+        Mock::given(method("POST"))
+            .and(path("/xrpc/com.atproto.repo.uploadBlob"))
+            .and(header("Authorization", "Bearer access"))
+            .and(header("Content-Type", "image/png"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "blob": { "$type": "blob", "ref": { "$link": "bafkrei" }, "mimeType": "image/png", "size": 4 }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/xrpc/com.atproto.repo.createRecord"))
+            .and(header("Authorization", "Bearer access"))
+            .and(body_partial_json(json!({
+                "repo": "did:plc:freegames",
+                "collection": "app.bsky.feed.post",
+                "record": {
+                    "$type": "app.bsky.feed.post",
+                    "text": TEXT,
+                    "facets": [
+                        { "index": { "byteStart": 41, "byteEnd": 47 }, "features": [{ "$type": "app.bsky.richtext.facet#tag", "tag": "Steam" }] },
+                        { "index": { "byteStart": 67, "byteEnd": 94 }, "features": [{ "$type": "app.bsky.richtext.facet#link", "uri": "https://icudev.xyz/the_game" }] },
+                        { "index": { "byteStart": 96, "byteEnd": 106 }, "features": [{ "$type": "app.bsky.richtext.facet#tag", "tag": "FreeGames" }] },
+                        { "index": { "byteStart": 107, "byteEnd": 116 }, "features": [{ "$type": "app.bsky.richtext.facet#tag", "tag": "The_Game" }] },
+                        { "index": { "byteStart": 117, "byteEnd": 128 }, "features": [{ "$type": "app.bsky.richtext.facet#tag", "tag": "SteamDeals" }] },
+                    ],
+                    "embed": {
+                        "$type": "app.bsky.embed.external",
+                        "external": {
+                            "uri": "https://icudev.xyz/the_game",
+                            "title": "The Game",
+                            "thumb": { "$type": "blob", "ref": { "$link": "bafkrei" } },
+                        },
+                    },
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "uri": "at://did:plc:freegames/app.bsky.feed.post/1",
+                "cid": "bafyrei",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = BlueskyPlatform::with_pds(server.uri(), "freegames.bsky.social".to_string(), "app-password".to_string());
+
+        platform.post(&make_test_post(true)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_renews_expired_session() {
+        let server = MockServer::start().await;
+        mock_session(&server, "expired").await;
+        mock_session(&server, "renewed").await;
+
+        Mock::given(method("POST"))
+            .and(path("/xrpc/com.atproto.repo.uploadBlob"))
+            .and(header("Authorization", "Bearer expired"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({ "error": "ExpiredToken", "message": "Token has expired" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/xrpc/com.atproto.repo.uploadBlob"))
+            .and(header("Authorization", "Bearer renewed"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "blob": { "$type": "blob" } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/xrpc/com.atproto.repo.createRecord"))
+            .and(header("Authorization", "Bearer renewed"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "uri": "at://1", "cid": "1" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = BlueskyPlatform::with_pds(server.uri(), "freegames.bsky.social".to_string(), "app-password".to_string());
+
+        platform.post(&make_test_post(true)).await.unwrap();
+    }
+}
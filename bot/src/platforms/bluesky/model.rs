@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Debug)]
+pub struct CreateSessionRequest {
+    pub identifier: String,
+    pub password: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub access_jwt: String,
+    // pub refresh_jwt: String,
+    // pub handle: String,
+    pub did: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UploadBlobResponse {
+    // Passed on as is, the record only has to reference the blob
+    pub blob: Value,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct XrpcError {
+    pub error: String,
+    pub message: Option<String>,
+}
+
+impl std::fmt::Display for XrpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.message {
+            Some(ref message) => write!(f, "{}: {message}", self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+impl std::error::Error for XrpcError {}
+
+#[derive(Serialize, Debug)]
+pub struct CreateRecordRequest {
+    pub repo: String,
+    pub collection: String,
+    pub record: PostRecord,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PostRecord {
+    #[serde(rename = "$type")]
+    pub record_type: String,
+    pub text: String,
+    pub created_at: String,
+    pub langs: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub facets: Vec<Facet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed: Option<ExternalEmbed>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Facet {
+    pub index: ByteSlice,
+    pub features: Vec<FacetFeature>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ByteSlice {
+    pub byte_start: usize,
+    pub byte_end: usize,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "$type")]
+pub enum FacetFeature {
+    #[serde(rename = "app.bsky.richtext.facet#link")]
+    Link { uri: String },
+    #[serde(rename = "app.bsky.richtext.facet#tag")]
+    Tag { tag: String },
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExternalEmbed {
+    #[serde(rename = "$type")]
+    pub embed_type: String,
+    pub external: External,
+}
+
+#[derive(Serialize, Debug)]
+pub struct External {
+    pub uri: String,
+    pub title: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb: Option<Value>,
+}
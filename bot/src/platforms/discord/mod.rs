@@ -0,0 +1,352 @@
+mod model;
+
+use std::env;
+use std::error::Error;
+use std::time::Duration;
+use chrono::NaiveDate;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use utils::model::{OfferKind, PostKind};
+use crate::platforms::{split_list, Deliveries, Platform, Post};
+
+const DISCORD_API_URL: &str = "https://discord.com/api/v10";
+const DISCORD_EMBED_COLOR: u32 = 0x5865F2;
+const DISCORD_MAX_RETRIES: usize = 3;
+// Longer waits mean a global rate limit, the game is tried again in the next loop instead
+const DISCORD_MAX_RETRY_AFTER: f64 = 60.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiscordTarget {
+    Webhook(String),
+    Channel(String),
+}
+
+pub struct DiscordPlatform {
+    http: Client,
+    api_url: String,
+    bot_token: Option<String>,
+    targets: Vec<DiscordTarget>,
+    deliveries: Deliveries,
+}
+
+impl DiscordPlatform {
+    pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        let mut targets = split_list(&env::var("DISCORD_WEBHOOK_URLS").unwrap_or_default())
+            .map(DiscordTarget::Webhook)
+            .collect::<Vec<_>>();
+
+        let bot_token = env::var("DISCORD_BOT_TOKEN").ok().filter(|token| !token.is_empty());
+        if bot_token.is_some() {
+            targets.extend(split_list(&env::var("DISCORD_CHANNEL_IDS")?).map(DiscordTarget::Channel));
+        }
+
+        if targets.is_empty() {
+            return Err("Neither DISCORD_WEBHOOK_URLS nor DISCORD_CHANNEL_IDS are set".into());
+        }
+
+        Ok(Self::with_targets(DISCORD_API_URL.to_string(), bot_token, targets))
+    }
+
+    pub(crate) fn with_targets(api_url: String, bot_token: Option<String>, targets: Vec<DiscordTarget>) -> Self {
+        Self {
+            http: Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            bot_token,
+            targets,
+            deliveries: Deliveries::default(),
+        }
+    }
+
+    fn make_request(&self, target: &DiscordTarget) -> Result<RequestBuilder, Box<dyn Error>> {
+        match target {
+            DiscordTarget::Webhook(url) => Ok(self.http.post(url)),
+            DiscordTarget::Channel(channel_id) => {
+                let Some(ref bot_token) = self.bot_token else {
+                    return Err("channels can only be posted to with a bot token".into());
+                };
+
+                Ok(self.http.post(format!("{}/channels/{channel_id}/messages", self.api_url))
+                    .header("Authorization", format!("Bot {bot_token}")))
+            },
+        }
+    }
+
+    async fn send(&self, target: &DiscordTarget, message: &model::Message) -> Result<(), Box<dyn Error>> {
+        for _ in 0..=DISCORD_MAX_RETRIES {
+            let request = self.make_request(target)?;
+            let res = request
+                .json(message)
+                .send()
+                .await
+                .map_err(reqwest::Error::without_url)?;
+
+            if res.status() == StatusCode::TOO_MANY_REQUESTS {
+                let retry_after = get_retry_after(res).await;
+                if retry_after > DISCORD_MAX_RETRY_AFTER {
+                    return Err(format!("rate limited for {retry_after} seconds").into());
+                }
+
+                log::debug!("Rate limited by Discord, retrying in {retry_after} seconds.");
+                tokio::time::sleep(Duration::from_secs_f64(retry_after)).await;
+                continue;
+            }
+
+            let res = res.error_for_status().map_err(reqwest::Error::without_url)?;
+
+            // Wait for the bucket to refill instead of running into the limit with the next message
+            if header_value(&res, "X-RateLimit-Remaining") == Some(0.0) {
+                let reset_after = header_value(&res, "X-RateLimit-Reset-After").unwrap_or(1.0);
+                tokio::time::sleep(Duration::from_secs_f64(reset_after.min(DISCORD_MAX_RETRY_AFTER))).await;
+            }
+
+            return Ok(());
+        }
+
+        Err(format!("still rate limited after {DISCORD_MAX_RETRIES} retries").into())
+    }
+}
+
+#[async_trait::async_trait]
+impl Platform for DiscordPlatform {
+    fn name(&self) -> String {
+        String::from("Discord")
+    }
+
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        let message = model::Message {
+            embeds: vec![make_embed(post)],
+        };
+
+        let mut failed = 0;
+        for target in &self.targets {
+            let name = target_name(target);
+            if self.deliveries.is_delivered(post, &name) {
+                continue;
+            }
+
+            match self.send(target, &message).await {
+                Ok(()) => self.deliveries.delivered(post, &name),
+                Err(e) => {
+                    log::error!("Couldn't post to Discord {name}: {e}");
+                    failed += 1;
+                },
+            }
+        }
+
+        if failed > 0 {
+            return Err(format!("{failed} of {} Discord targets failed", self.targets.len()).into());
+        }
+
+        self.deliveries.forget(post);
+        Ok(())
+    }
+}
+
+fn make_embed(post: &Post) -> model::Embed {
+    let game = &post.game;
+
+    let mut fields = vec![
+        make_field("Store", game.store.to_string()),
+        make_field("Type", game.game_type.to_string()),
+    ];
+    if let Some(ref original_price) = game.original_price {
+        fields.push(make_field("Original price", original_price.to_string()));
+    }
+    match (&post.kind, &game.offer_kind) {
+        (PostKind::Upcoming, _) => {
+            fields.push(make_field("Free from", make_timestamp(game.offer_from.unwrap_or(game.offer_until))));
+        },
+        // Free-to-play games stay free
+        (PostKind::Free, OfferKind::FreeToPlay) => {},
+        (PostKind::Free, _) => {
+            fields.push(make_field("Free until", make_timestamp(game.offer_until)));
+        },
+    }
+
+    model::Embed {
+        title: game.title.clone(),
+        url: game.url.clone(),
+        // The headline of the post, links and hashtags are already part of the embed
+        description: post.text.split("\n\n").next().unwrap_or_default().to_string(),
+        color: DISCORD_EMBED_COLOR,
+        fields,
+        image: game.image_url.clone().map(|url| model::EmbedImage { url }),
+    }
+}
+
+fn make_field(name: &str, value: String) -> model::EmbedField {
+    model::EmbedField {
+        name: name.to_string(),
+        value,
+        inline: true,
+    }
+}
+
+/// Discord renders timestamps in the reader's timezone, noon keeps the date the same for most of them.
+fn make_timestamp(date: NaiveDate) -> String {
+    let timestamp = date.and_hms_opt(12, 0, 0).unwrap().and_utc().timestamp();
+
+    format!("<t:{timestamp}:D> (<t:{timestamp}:R>)")
+}
+
+async fn get_retry_after(res: Response) -> f64 {
+    let header = header_value(&res, "Retry-After");
+
+    match res.json::<model::RateLimit>().await {
+        Ok(rate_limit) => rate_limit.retry_after,
+        Err(_) => header.unwrap_or(1.0),
+    }
+}
+
+fn header_value(res: &Response, name: &str) -> Option<f64> {
+    res.headers().get(name)?.to_str().ok()?.parse().ok()
+}
+
+// Webhook URLs contain their token, so they must not end up in the logs
+fn target_name(target: &DiscordTarget) -> String {
+    match target {
+        DiscordTarget::Webhook(url) => {
+            let id = url.trim_end_matches('/').rsplit('/').nth(1).unwrap_or_default();
+            format!("webhook {id}")
+        },
+        DiscordTarget::Channel(channel_id) => format!("channel {channel_id}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use crate::platforms::tests::make_test_post;
+    use super::*;
+
+    #[test]
+    fn test_target_name() {
+        assert_eq!(target_name(&DiscordTarget::Webhook("https://discord.com/api/webhooks/123/secret".to_string())), "webhook 123");
+        assert_eq!(target_name(&DiscordTarget::Channel("456".to_string())), "channel 456");
+    }
+
+    #[tokio::test]
+    async fn test_post() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/webhooks/123/secret"))
+            .and(body_partial_json(json!({
+                "embeds": [{
+                    "title": "The Game",
+                    "url": "https://icudev.xyz/the_game",
+                    "description": "[ Game ] \"The Game\" is currently free on #Steam until 2025-06-15.",
+                    "fields": [
+                        { "name": "Store", "value": "Steam", "inline": true },
+                        { "name": "Type", "value": "Game", "inline": true },
+                        { "name": "Original price", "value": "$19.99", "inline": true },
+                        { "name": "Free until", "value": "<t:1749988800:D> (<t:1749988800:R>)", "inline": true },
+                    ],
+                    "image": { "url": "https://icudev.xyz/the_game.png" },
+                }],
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/channels/456/messages"))
+            .and(header("Authorization", "Bot token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "1" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = DiscordPlatform::with_targets(server.uri(), Some("token".to_string()), vec![
+            DiscordTarget::Webhook(format!("{}/api/webhooks/123/secret", server.uri())),
+            DiscordTarget::Channel("456".to_string()),
+        ]);
+
+        platform.post(&make_test_post(true)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_retries_when_rate_limited() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/webhooks/123/secret"))
+            .respond_with(ResponseTemplate::new(429).set_body_json(json!({
+                "message": "You are being rate limited.",
+                "retry_after": 0.05,
+                "global": false,
+            })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/webhooks/123/secret"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = DiscordPlatform::with_targets(server.uri(), None, vec![
+            DiscordTarget::Webhook(format!("{}/api/webhooks/123/secret", server.uri())),
+        ]);
+
+        platform.post(&make_test_post(false)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_retries_failed_targets_only() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/webhooks/123/secret"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/channels/456/messages"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/channels/456/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "1" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = DiscordPlatform::with_targets(server.uri(), Some("token".to_string()), vec![
+            DiscordTarget::Webhook(format!("{}/api/webhooks/123/secret", server.uri())),
+            DiscordTarget::Channel("456".to_string()),
+        ]);
+        let post = make_test_post(false);
+
+        // The webhook got the game, but the post as a whole failed and is tried again
+        assert!(platform.post(&post).await.is_err());
+        platform.post(&post).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_fails_when_all_targets_fail() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let platform = DiscordPlatform::with_targets(server.uri(), None, vec![
+            DiscordTarget::Webhook(format!("{}/api/webhooks/123/secret", server.uri())),
+        ]);
+
+        assert!(platform.post(&make_test_post(false)).await.is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Debug)]
+pub struct Message {
+    pub embeds: Vec<Embed>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Embed {
+    pub title: String,
+    pub url: String,
+    pub description: String,
+    pub color: u32,
+    pub fields: Vec<EmbedField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<EmbedImage>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EmbedImage {
+    pub url: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RateLimit {
+    pub retry_after: f64,
+    // pub global: bool,
+    // pub message: String,
+}
@@ -0,0 +1,219 @@
+use std::env;
+use std::error::Error;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use crate::platforms::{escape_html, split_list, Platform, Post};
+
+/// Sends all games of a dispatch cycle as a single digest email.
+pub struct EmailPlatform {
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    recipients: Vec<Mailbox>,
+}
+
+impl EmailPlatform {
+    pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        let host = env::var("SMTP_HOST")?;
+        let mut builder = match env::var("SMTP_SECURITY").unwrap_or_default().as_str() {
+            "" | "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)?,
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&host)?,
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+            security => return Err(format!("Unknown SMTP_SECURITY: {security}").into()),
+        };
+
+        if let Some(port) = env::var("SMTP_PORT").ok().filter(|port| !port.is_empty()) {
+            builder = builder.port(port.parse()?);
+        }
+        if let Ok(username) = env::var("SMTP_USERNAME") && !username.is_empty() {
+            builder = builder.credentials(Credentials::new(username, env::var("SMTP_PASSWORD")?));
+        }
+
+        let recipients = split_list(&env::var("EMAIL_RECIPIENTS")?)
+            .map(|recipient| recipient.parse())
+            .collect::<Result<Vec<Mailbox>, _>>()?;
+        if recipients.is_empty() {
+            return Err("EMAIL_RECIPIENTS is empty".into());
+        }
+
+        Ok(Self::with_mailer(builder.build(), env::var("EMAIL_FROM")?.parse()?, recipients))
+    }
+
+    pub(crate) fn with_mailer(mailer: AsyncSmtpTransport<Tokio1Executor>, from: Mailbox, recipients: Vec<Mailbox>) -> Self {
+        Self {
+            mailer,
+            from,
+            recipients,
+        }
+    }
+
+    fn make_message(&self, posts: &[Post]) -> Result<Message, Box<dyn Error>> {
+        let subject = match posts {
+            [post] => format!("Free Games: {}", post.game.title),
+            _ => format!("Free Games: {} new games", posts.len()),
+        };
+
+        // Recipients are hidden from each other, the digest is addressed to ourselves
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .to(self.from.clone())
+            .subject(subject);
+        for recipient in &self.recipients {
+            builder = builder.bcc(recipient.clone());
+        }
+
+        Ok(builder.multipart(MultiPart::alternative_plain_html(make_plain_text(posts), make_html(posts)))?)
+    }
+}
+
+#[async_trait::async_trait]
+impl Platform for EmailPlatform {
+    fn name(&self) -> String {
+        String::from("Email")
+    }
+
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        self.post_batch(std::slice::from_ref(post)).await
+    }
+
+    fn is_batched(&self) -> bool {
+        true
+    }
+
+    async fn post_batch(&self, posts: &[Post]) -> Result<(), Box<dyn Error>> {
+        let message = self.make_message(posts)?;
+
+        // Only returns once the server accepted the message, rejections are errors
+        self.mailer.send(message).await?;
+
+        Ok(())
+    }
+}
+
+fn headline(post: &Post) -> &str {
+    post.text.split("\n\n").next().unwrap_or_default()
+}
+
+fn make_plain_text(posts: &[Post]) -> String {
+    posts.iter()
+        .map(|post| format!("{}\n{}", headline(post), post.game.url))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn make_html(posts: &[Post]) -> String {
+    let games = posts.iter()
+        .map(|post| {
+            let image = match post.game.image_url {
+                Some(ref image_url) => format!(
+                    "<img src=\"{}\" alt=\"{}\" width=\"460\"><br>",
+                    escape_html(image_url),
+                    escape_html(&post.game.title),
+                ),
+                None => String::new(),
+            };
+
+            format!(
+                "<h2><a href=\"{}\">{}</a></h2>{image}<p>{}</p>",
+                escape_html(&post.game.url),
+                escape_html(&post.game.title),
+                escape_html(headline(post)),
+            )
+        })
+        .collect::<String>();
+
+    format!("<!DOCTYPE html><html><body>{games}</body></html>")
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+    use crate::platforms::tests::make_test_post;
+    use super::*;
+
+    /// Accepts a single SMTP session and returns everything the client sent.
+    async fn spawn_smtp_sink(data_reply: &'static str) -> (u16, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let mut session = String::new();
+            let mut in_data = false;
+
+            writer.write_all(b"220 localhost ESMTP sink\r\n").await.unwrap();
+            while let Ok(Some(line)) = lines.next_line().await {
+                session.push_str(&line);
+                session.push('\n');
+
+                let reply = if in_data {
+                    if line != "." {
+                        continue;
+                    }
+                    in_data = false;
+                    data_reply
+                } else if line.starts_with("DATA") {
+                    in_data = true;
+                    "354 End data with <CR><LF>.<CR><LF>"
+                } else if line.starts_with("QUIT") {
+                    writer.write_all(b"221 Bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    "250 OK"
+                };
+                writer.write_all(format!("{reply}\r\n").as_bytes()).await.unwrap();
+            }
+
+            session
+        });
+
+        (port, handle)
+    }
+
+    fn make_platform(port: u16) -> EmailPlatform {
+        let mailer = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous("127.0.0.1")
+            .port(port)
+            .build();
+
+        EmailPlatform::with_mailer(
+            mailer,
+            "Free Games <bot@icudev.xyz>".parse().unwrap(),
+            vec!["alice@example.com".parse().unwrap(), "bob@example.com".parse().unwrap()],
+        )
+    }
+
+    #[test]
+    fn test_make_html() {
+        assert_eq!(
+            make_html(&[make_test_post(false)]),
+            "<!DOCTYPE html><html><body><h2><a href=\"https://icudev.xyz/the_game\">The Game</a></h2><img src=\"https://icudev.xyz/the_game.png\" alt=\"The Game\" width=\"460\"><br><p>[ Game ] &quot;The Game&quot; is currently free on #Steam until 2025-06-15.</p></body></html>",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_batch() {
+        let (port, sink) = spawn_smtp_sink("250 Queued").await;
+        let mut second_post = make_test_post(false);
+        second_post.game.title = "The Other Game".to_string();
+
+        make_platform(port).post_batch(&[make_test_post(false), second_post]).await.unwrap();
+
+        let session = sink.await.unwrap();
+        assert!(session.contains("RCPT TO:<alice@example.com>"));
+        assert!(session.contains("RCPT TO:<bob@example.com>"));
+        assert!(session.contains("Subject: Free Games: 2 new games"));
+        assert!(session.contains("Content-Type: multipart/alternative"));
+        assert!(!session.contains("Bcc:"));
+    }
+
+    #[tokio::test]
+    async fn test_post_batch_fails_when_rejected() {
+        let (port, _) = spawn_smtp_sink("554 Transaction failed").await;
+
+        assert!(make_platform(port).post_batch(&[make_test_post(false)]).await.is_err());
+    }
+}
@@ -0,0 +1,302 @@
+mod model;
+
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+use reqwest::Client;
+use reqwest::multipart::{Form, Part};
+use tokio::sync::OnceCell;
+use crate::platforms::{shorten_text, Platform, Post, PostImage};
+
+// Defaults of vanilla Mastodon, used when the instance doesn't tell us its limits
+const DEFAULT_MAX_CHARACTERS: usize = 500;
+const DEFAULT_CHARACTERS_PER_URL: usize = 23;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Visibility {
+    Public,
+    Unlisted,
+    Private,
+    Direct,
+}
+
+impl Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Visibility::Public => write!(f, "public"),
+            Visibility::Unlisted => write!(f, "unlisted"),
+            Visibility::Private => write!(f, "private"),
+            Visibility::Direct => write!(f, "direct"),
+        }
+    }
+}
+
+impl FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "public" => Ok(Visibility::Public),
+            "unlisted" => Ok(Visibility::Unlisted),
+            "private" => Ok(Visibility::Private),
+            "direct" => Ok(Visibility::Direct),
+            _ => Err(format!("Unknown Mastodon visibility: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct StatusLimits {
+    max_characters: usize,
+    characters_per_url: usize,
+}
+
+pub struct MastodonPlatform {
+    http: Client,
+    instance_url: String,
+    access_token: String,
+    visibility: Visibility,
+    spoiler_text: Option<String>,
+    limits: OnceCell<StatusLimits>,
+}
+
+impl MastodonPlatform {
+    pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        let visibility = match env::var("MASTODON_VISIBILITY") {
+            Ok(visibility) => visibility.parse()?,
+            Err(_) => Visibility::Public,
+        };
+
+        Ok(Self::with_instance(
+            env::var("MASTODON_INSTANCE_URL")?,
+            env::var("MASTODON_ACCESS_TOKEN")?,
+            visibility,
+            env::var("MASTODON_SPOILER_TEXT").ok().filter(|text| !text.is_empty()),
+        ))
+    }
+
+    pub(crate) fn with_instance(instance_url: String, access_token: String, visibility: Visibility, spoiler_text: Option<String>) -> Self {
+        Self {
+            http: Client::new(),
+            instance_url: instance_url.trim_end_matches('/').to_string(),
+            access_token,
+            visibility,
+            spoiler_text,
+            limits: OnceCell::new(),
+        }
+    }
+
+    async fn get_limits(&self) -> StatusLimits {
+        if let Some(limits) = self.limits.get() {
+            return *limits;
+        }
+
+        match self.fetch_limits().await {
+            Ok(limits) => {
+                let _ = self.limits.set(limits);
+                limits
+            },
+            // Not cached, so the next post asks the instance again
+            Err(e) => {
+                log::warn!("Couldn't get status limits of {}: {e}", self.instance_url);
+                StatusLimits {
+                    max_characters: DEFAULT_MAX_CHARACTERS,
+                    characters_per_url: DEFAULT_CHARACTERS_PER_URL,
+                }
+            },
+        }
+    }
+
+    async fn fetch_limits(&self) -> Result<StatusLimits, Box<dyn Error>> {
+        let instance = self.http.get(format!("{}/api/v2/instance", self.instance_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<model::Instance>()
+            .await?;
+
+        Ok(StatusLimits {
+            max_characters: instance.configuration.statuses.max_characters,
+            characters_per_url: instance.configuration.statuses.characters_reserved_per_url,
+        })
+    }
+
+    async fn upload_image(&self, image: &PostImage) -> Result<String, Box<dyn Error>> {
+        let part = Part::bytes(image.image.data.clone())
+            .file_name("image")
+            .mime_str(&image.image.content_type)?;
+        let form = Form::new()
+            .part("file", part)
+            .text("description", image.alt_text.clone());
+
+        let attachment = self.http.post(format!("{}/api/v2/media", self.instance_url))
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<model::MediaAttachment>()
+            .await?;
+
+        Ok(attachment.id)
+    }
+}
+
+#[async_trait::async_trait]
+impl Platform for MastodonPlatform {
+    fn name(&self) -> String {
+        String::from("Mastodon")
+    }
+
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        let limits = self.get_limits().await;
+        // The content warning counts towards the character limit as well
+        let spoiler_length = self.spoiler_text.as_ref().map_or(0, |text| text.chars().count());
+        let status = shorten_text(
+            &post.text,
+            limits.max_characters.saturating_sub(spoiler_length),
+            |text| status_length(text, limits.characters_per_url),
+        );
+
+        let mut media_ids = vec![];
+        if let Some(ref image) = post.image {
+            match self.upload_image(image).await {
+                Ok(media_id) => media_ids.push(media_id),
+                Err(e) => log::error!("Couldn't upload image to Mastodon: {e}"),
+            }
+        }
+
+        self.http.post(format!("{}/api/v1/statuses", self.instance_url))
+            .bearer_auth(&self.access_token)
+            .json(&model::NewStatus {
+                status,
+                visibility: self.visibility.to_string(),
+                spoiler_text: self.spoiler_text.clone(),
+                media_ids,
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Mastodon counts every link with the same length, no matter how long it actually is.
+fn status_length(text: &str, characters_per_url: usize) -> usize {
+    text.split_whitespace()
+        .filter(|word| word.starts_with("http://") || word.starts_with("https://"))
+        .fold(text.chars().count(), |length, url| length - url.chars().count() + characters_per_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use crate::platforms::tests::make_test_post;
+    use super::*;
+
+    const TEXT: &str = "[ Game ] \"The Game\" is currently free on #Steam until 2025-06-15.\n\nhttps://icudev.xyz/the_game\n\n#FreeGames #The_Game #SteamDeals";
+
+    async fn mock_instance(server: &MockServer, max_characters: usize) {
+        Mock::given(method("GET"))
+            .and(path("/api/v2/instance"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "domain": "mastodon.example",
+                "configuration": {
+                    "statuses": {
+                        "max_characters": max_characters,
+                        "max_media_attachments": 4,
+                        "characters_reserved_per_url": 23
+                    }
+                }
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_status_length() {
+        assert_eq!(status_length("Free: https://icudev.xyz/a/very/long/path/to/the_game", 23), 29);
+        assert_eq!(status_length(TEXT, 23), TEXT.chars().count() - 4);
+    }
+
+    #[tokio::test]
+    async fn test_post() {
+        let server = MockServer::start().await;
+        mock_instance(&server, 500).await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/v2/media"))
+            .and(header("Authorization", "Bearer token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "1234", "type": "image" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/v1/statuses"))
+            .and(header("Authorization", "Bearer token"))
+            .and(body_partial_json(json!({
+                "status": TEXT,
+                "visibility": "unlisted",
+                "spoiler_text": "Free games",
+                "media_ids": ["1234"],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "1" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = MastodonPlatform::with_instance(
+            server.uri(),
+            "token".to_string(),
+            Visibility::Unlisted,
+            Some("Free games".to_string()),
+        );
+        let post = make_test_post(true);
+
+        platform.post(&post).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_respects_character_limit() {
+        let server = MockServer::start().await;
+        mock_instance(&server, 100).await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/v1/statuses"))
+            .and(body_partial_json(json!({
+                "status": "[ Game ] \"The Game\" is currently free on #Steam until 2025-06-15.\n\nhttps://icudev.xyz/the_game",
+                "visibility": "public",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "1" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = MastodonPlatform::with_instance(server.uri(), "token".to_string(), Visibility::Public, None);
+        let post = make_test_post(false);
+
+        platform.post(&post).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_fails_on_error() {
+        let server = MockServer::start().await;
+        mock_instance(&server, 500).await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/v1/statuses"))
+            .respond_with(ResponseTemplate::new(422))
+            .mount(&server)
+            .await;
+
+        let platform = MastodonPlatform::with_instance(server.uri(), "token".to_string(), Visibility::Public, None);
+        let post = make_test_post(false);
+
+        assert!(platform.post(&post).await.is_err());
+    }
+}
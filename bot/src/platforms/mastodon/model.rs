@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug)]
+pub struct Instance {
+    pub configuration: InstanceConfiguration,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct InstanceConfiguration {
+    pub statuses: StatusesConfiguration,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StatusesConfiguration {
+    pub max_characters: usize,
+    pub characters_reserved_per_url: usize,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MediaAttachment {
+    pub id: String,
+    // #[serde(rename = "type")]
+    // pub media_type: String,
+    // pub url: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct NewStatus {
+    pub status: String,
+    pub visibility: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spoiler_text: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub media_ids: Vec<String>,
+}
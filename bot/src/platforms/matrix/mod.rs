@@ -0,0 +1,274 @@
+mod model;
+
+use std::env;
+use std::error::Error;
+use std::time::Duration;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::Client;
+use crate::platforms::{escape_html, split_list, Deliveries, Platform, Post};
+
+// Room ids contain `!` and `:`, which have to be escaped in the path
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+const MATRIX_MAX_RETRIES: usize = 3;
+// Longer waits are left to the next loop, which sends the same transaction again
+const MATRIX_MAX_RETRY_AFTER_MS: u64 = 60_000;
+
+pub struct MatrixPlatform {
+    http: Client,
+    homeserver_url: String,
+    access_token: String,
+    room_ids: Vec<String>,
+    deliveries: Deliveries,
+}
+
+impl MatrixPlatform {
+    pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        let room_ids = split_list(&env::var("MATRIX_ROOM_IDS")?).collect::<Vec<_>>();
+        if room_ids.is_empty() {
+            return Err("MATRIX_ROOM_IDS is empty".into());
+        }
+
+        Ok(Self::with_rooms(
+            env::var("MATRIX_HOMESERVER_URL")?,
+            env::var("MATRIX_ACCESS_TOKEN")?,
+            room_ids,
+        ))
+    }
+
+    pub(crate) fn with_rooms(homeserver_url: String, access_token: String, room_ids: Vec<String>) -> Self {
+        Self {
+            http: Client::new(),
+            homeserver_url: homeserver_url.trim_end_matches('/').to_string(),
+            access_token,
+            room_ids,
+            deliveries: Deliveries::default(),
+        }
+    }
+
+    async fn send_message(&self, room_id: &str, txn_id: &str, message: &model::RoomMessage) -> Result<(), Box<dyn Error>> {
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
+            self.homeserver_url,
+            utf8_percent_encode(room_id, PATH_SEGMENT),
+            utf8_percent_encode(txn_id, PATH_SEGMENT),
+        );
+
+        for _ in 0..=MATRIX_MAX_RETRIES {
+            let res = self.http.put(&url)
+                .bearer_auth(&self.access_token)
+                .json(message)
+                .send()
+                .await?;
+
+            if res.status().is_success() {
+                return Ok(());
+            }
+
+            let status = res.status();
+            let Ok(error) = res.json::<model::MatrixError>().await else {
+                return Err(format!("homeserver responded with {status}").into());
+            };
+
+            if error.errcode != "M_LIMIT_EXCEEDED" {
+                return Err(format!("{}: {}", error.errcode, error.error.unwrap_or_default()).into());
+            }
+
+            let retry_after_ms = error.retry_after_ms.unwrap_or(1000);
+            if retry_after_ms > MATRIX_MAX_RETRY_AFTER_MS {
+                return Err(format!("rate limited for {retry_after_ms} ms").into());
+            }
+
+            log::debug!("Rate limited by Matrix homeserver, retrying in {retry_after_ms} ms.");
+            tokio::time::sleep(Duration::from_millis(retry_after_ms)).await;
+        }
+
+        Err(format!("still rate limited after {MATRIX_MAX_RETRIES} retries").into())
+    }
+}
+
+#[async_trait::async_trait]
+impl Platform for MatrixPlatform {
+    fn name(&self) -> String {
+        String::from("Matrix")
+    }
+
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        let message = model::RoomMessage {
+            msgtype: "m.text".to_string(),
+            body: post.text.clone(),
+            format: "org.matrix.custom.html".to_string(),
+            formatted_body: make_formatted_body(post),
+        };
+        // The homeserver ignores transactions it has already seen, which also covers rooms that
+        // got the game before the bot restarted
+        let txn_id = format!("{}-{}-{}", post.game.store, post.game.id, post.kind);
+
+        let mut failed = 0;
+        for room_id in &self.room_ids {
+            if self.deliveries.is_delivered(post, room_id) {
+                continue;
+            }
+
+            match self.send_message(room_id, &txn_id, &message).await {
+                Ok(()) => self.deliveries.delivered(post, room_id),
+                Err(e) => {
+                    log::error!("Couldn't post to Matrix room {room_id}: {e}");
+                    failed += 1;
+                },
+            }
+        }
+
+        if failed > 0 {
+            return Err(format!("{failed} of {} Matrix rooms failed", self.room_ids.len()).into());
+        }
+
+        self.deliveries.forget(post);
+        Ok(())
+    }
+}
+
+fn make_formatted_body(post: &Post) -> String {
+    let quoted_title = escape_html(&format!("\"{}\"", post.game.title));
+    let bold_title = format!("<b>{}</b>", escape_html(&post.game.title));
+
+    post.text.split("\n\n")
+        .map(|paragraph| if paragraph == post.game.url {
+            format!("<a href=\"{0}\">{0}</a>", escape_html(paragraph))
+        } else {
+            escape_html(paragraph).replacen(&quoted_title, &bold_title, 1)
+        })
+        .collect::<Vec<_>>()
+        .join("<br><br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use crate::platforms::tests::make_test_post;
+    use super::*;
+
+    const ROOM_PATH: &str = "/_matrix/client/v3/rooms/%21ops%3Aicudev.xyz/send/m.room.message/Steam-unique_id-Free";
+
+    #[test]
+    fn test_formatted_body() {
+        assert_eq!(
+            make_formatted_body(&make_test_post(false)),
+            "[ Game ] <b>The Game</b> is currently free on #Steam until 2025-06-15.<br><br><a href=\"https://icudev.xyz/the_game\">https://icudev.xyz/the_game</a><br><br>#FreeGames #The_Game #SteamDeals",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post() {
+        let server = MockServer::start().await;
+        let post = make_test_post(false);
+
+        Mock::given(method("PUT"))
+            .and(path(ROOM_PATH))
+            .and(header("Authorization", "Bearer token"))
+            .and(body_partial_json(json!({
+                "msgtype": "m.text",
+                "body": post.text,
+                "format": "org.matrix.custom.html",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "event_id": "$event" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = MatrixPlatform::with_rooms(server.uri(), "token".to_string(), vec!["!ops:icudev.xyz".to_string()]);
+
+        platform.post(&post).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_retries_when_rate_limited() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("PUT"))
+            .and(path(ROOM_PATH))
+            .respond_with(ResponseTemplate::new(429).set_body_json(json!({
+                "errcode": "M_LIMIT_EXCEEDED",
+                "error": "Too many requests",
+                "retry_after_ms": 50,
+            })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("PUT"))
+            .and(path(ROOM_PATH))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "event_id": "$event" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = MatrixPlatform::with_rooms(server.uri(), "token".to_string(), vec!["!ops:icudev.xyz".to_string()]);
+
+        platform.post(&make_test_post(false)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_fails_when_forbidden() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+                "errcode": "M_FORBIDDEN",
+                "error": "User is not in the room",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = MatrixPlatform::with_rooms(server.uri(), "token".to_string(), vec!["!ops:icudev.xyz".to_string()]);
+
+        assert!(platform.post(&make_test_post(false)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_post_retries_failed_rooms_only() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("PUT"))
+            .and(path(ROOM_PATH))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "event_id": "$event" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("PUT"))
+            .and(path("/_matrix/client/v3/rooms/%21games%3Aicudev.xyz/send/m.room.message/Steam-unique_id-Free"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+                "errcode": "M_FORBIDDEN",
+                "error": "User is not in the room",
+            })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("PUT"))
+            .and(path("/_matrix/client/v3/rooms/%21games%3Aicudev.xyz/send/m.room.message/Steam-unique_id-Free"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "event_id": "$event" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = MatrixPlatform::with_rooms(server.uri(), "token".to_string(), vec![
+            "!ops:icudev.xyz".to_string(),
+            "!games:icudev.xyz".to_string(),
+        ]);
+        let post = make_test_post(false);
+
+        assert!(platform.post(&post).await.is_err());
+        platform.post(&post).await.unwrap();
+    }
+}
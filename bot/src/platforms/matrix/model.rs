@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Debug)]
+pub struct RoomMessage {
+    pub msgtype: String,
+    pub body: String,
+    pub format: String,
+    pub formatted_body: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MatrixError {
+    pub errcode: String,
+    pub error: Option<String>,
+    pub retry_after_ms: Option<u64>,
+}
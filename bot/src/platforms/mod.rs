@@ -1,15 +1,237 @@
+pub mod bluesky;
+pub mod discord;
+pub mod email;
+pub mod matrix;
+pub mod push;
+pub mod reddit;
+pub mod telegram;
+pub mod mastodon;
 pub mod twitter;
+pub mod webhook;
 
-use utils::model::{Game, GameStore};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use chrono::{NaiveDate, Utc};
+use utils::model::{Game, GameStore, Launcher, OfferKind, PostKind};
+use crate::media::{Image, ImageDownloader};
 
 #[async_trait::async_trait]
-pub trait Platform {
+pub trait Platform: Send + Sync {
     fn name(&self) -> String;
 
-    async fn post_game(&self, game: &Game) -> Result<(), Box<dyn std::error::Error>>;
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Batched platforms get all unposted games of a dispatch cycle at once through `post_batch`
+    /// instead of one `post` per game.
+    fn is_batched(&self) -> bool {
+        false
+    }
+
+    async fn post_batch(&self, posts: &[Post]) -> Result<(), Box<dyn std::error::Error>> {
+        for post in posts {
+            self.post(post).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A post rendered once per game and shared between all platforms.
+#[derive(Clone)]
+pub struct Post {
+    pub text: String,
+    pub game: Game,
+    pub kind: PostKind,
+    pub image: Option<PostImage>,
+}
+
+#[derive(Clone)]
+pub struct PostImage {
+    pub image: Arc<Image>,
+    pub alt_text: String,
+}
+
+/// Remembers which targets of a platform posting to several of them already got a post. A post
+/// that failed for some targets fails as a whole and is tried again in the next loop, where only
+/// the targets that didn't get it are posted to. This is kept in memory, a restart in between
+/// posts the game to all targets again.
+#[derive(Default)]
+pub struct Deliveries {
+    delivered: Mutex<HashSet<(String, String)>>,
+}
+
+impl Deliveries {
+    pub fn is_delivered(&self, post: &Post, target: &str) -> bool {
+        self.delivered.lock().unwrap().contains(&(post_key(post), target.to_string()))
+    }
+
+    pub fn delivered(&self, post: &Post, target: &str) {
+        self.delivered.lock().unwrap().insert((post_key(post), target.to_string()));
+    }
+
+    /// Called once every target got the post, it's recorded as posted from then on.
+    pub fn forget(&self, post: &Post) {
+        let key = post_key(post);
+        self.delivered.lock().unwrap().retain(|(post, _)| *post != key);
+    }
+}
+
+fn post_key(post: &Post) -> String {
+    format!("{}-{}-{}", post.game.store, post.game.id, post.kind)
+}
+
+pub async fn render_post(game: &Game, kind: &PostKind, images: &ImageDownloader) -> Post {
+    let image = match game.image_url {
+        Some(ref image_url) => match images.get(image_url).await {
+            Ok(image) => Some(PostImage {
+                image,
+                alt_text: format!("Store artwork of \"{}\"", game.title),
+            }),
+            // A missing image shouldn't keep the game from being posted
+            Err(e) => {
+                log::error!("Couldn't download image of game {}: {e}", game.id);
+                None
+            },
+        },
+        None => None,
+    };
+
+    Post {
+        text: make_post_text(game, kind),
+        game: game.clone(),
+        kind: kind.clone(),
+        image,
+    }
+}
+
+pub fn make_post_text(game: &Game, kind: &PostKind) -> String {
+    match kind {
+        PostKind::Free => make_text(game),
+        PostKind::Upcoming => make_upcoming_text(game, Utc::now().date_naive()),
+    }
 }
 
 pub fn make_text(game: &Game) -> String {
+    let offer = match game.offer_kind {
+        OfferKind::Keep => format!(
+            "is currently free on #{:?} until {}.{}",
+            game.store,
+            game.offer_until.format("%Y-%m-%d"),
+            make_redeem_text(game),
+        ),
+        OfferKind::TemporaryPlay => format!(
+            "can be played for free on #{:?} until {}, but you can't keep it afterwards.",
+            game.store,
+            game.offer_until.format("%Y-%m-%d"),
+        ),
+        OfferKind::FreeToPlay => format!(
+            "just launched as free-to-play on #{:?}.",
+            game.store,
+        ),
+        OfferKind::Unknown => format!(
+            "is free on #{:?} until {}.",
+            game.store,
+            game.offer_until.format("%Y-%m-%d"),
+        ),
+    };
+
+    format!(
+        "[ {} ] \"{}\" {}\n\n{}\n\n{}",
+        game.game_type,
+        game.title,
+        offer,
+        game.url,
+        make_hashtags(game),
+    )
+}
+
+pub fn make_upcoming_text(game: &Game, today: NaiveDate) -> String {
+    let offer_from = game.offer_from.unwrap_or(game.offer_until);
+
+    format!(
+        "[ {} ] \"{}\" will be free on #{:?} starting {}.\n\n{}\n\n{}",
+        make_lead_in(offer_from, today),
+        game.title,
+        game.store,
+        offer_from.format("%Y-%m-%d"),
+        game.url,
+        make_hashtags(game),
+    )
+}
+
+/// Says how far away the start of an offer is, counted in days from `today`.
+pub fn make_lead_in(offer_from: NaiveDate, today: NaiveDate) -> String {
+    match (offer_from - today).num_days() {
+        ..=0 => "Starting today".to_string(),
+        1 => "Coming tomorrow".to_string(),
+        days @ 2..=6 => format!("Coming in {days} days"),
+        7..=13 => "Coming next week".to_string(),
+        _ => "Coming soon".to_string(),
+    }
+}
+
+/// Shortens a text made by `make_post_text` until `length` of it fits into `max_length`. Hashtags
+/// are dropped first, then the headline is cut off, the link is always kept.
+pub fn shorten_text(text: &str, max_length: usize, length: impl Fn(&str) -> usize) -> String {
+    let mut paragraphs = text.split("\n\n").map(String::from).collect::<Vec<_>>();
+
+    while length(&paragraphs.join("\n\n")) > max_length && paragraphs.len() > 1 {
+        let hashtags = paragraphs.last_mut().unwrap();
+        if !hashtags.starts_with('#') {
+            break;
+        }
+
+        match hashtags.rsplit_once(' ') {
+            Some((rest, _)) => *hashtags = rest.to_string(),
+            None => {
+                paragraphs.pop();
+            },
+        }
+    }
+
+    let overflow = length(&paragraphs.join("\n\n")).saturating_sub(max_length);
+    if overflow > 0 {
+        let headline = &paragraphs[0];
+        let keep = headline.chars().count().saturating_sub(overflow + 1);
+        let mut shortened = headline.chars().take(keep).collect::<String>();
+        // Don't leave half a word behind
+        if let Some((words, _)) = shortened.rsplit_once(' ') {
+            shortened = words.to_string();
+        }
+        paragraphs[0] = format!("{}…", shortened.trim_end());
+    }
+
+    paragraphs.join("\n\n")
+}
+
+/// Splits a comma separated list from the environment, like the targets of a platform.
+pub fn split_list(list: &str) -> impl Iterator<Item = String> + '_ {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn make_redeem_text(game: &Game) -> String {
+    let launcher = match game.redeem_on {
+        Some(Launcher::AmazonGames) => "the Amazon Games app",
+        Some(Launcher::EpicGames) => "the Epic Games Store",
+        Some(Launcher::Gog) => "GOG",
+        Some(Launcher::LegacyGames) => "Legacy Games",
+        Some(Launcher::Unknown) | None => return String::new(),
+    };
+
+    format!(" Redeem it on {launcher}.")
+}
+
+fn make_hashtags(game: &Game) -> String {
     let mut hashtags = vec![];
     hashtags.push("#FreeGames".to_string());
     hashtags.push(format!("#{}", game.identifier));
@@ -20,25 +242,56 @@ pub fn make_text(game: &Game) -> String {
         },
         GameStore::EpicGames => {},
         GameStore::Gog => {},
+        GameStore::ItchIo => {
+            hashtags.push("#itchio".to_string());
+        },
+        GameStore::Humble => {
+            hashtags.push("#HumbleBundle".to_string());
+        },
+        GameStore::PrimeGaming => {
+            hashtags.push("#PrimeGaming".to_string());
+        },
         GameStore::Unknown => {},
     }
 
-    format!(
-        "[ {} ] \"{}\" is currently free on #{:?} until {}.\n\n{}\n\n{}",
-        game.game_type,
-        game.title,
-        game.store,
-        game.offer_until.format("%Y-%m-%d"),
-        game.url,
-        hashtags.join(" "),
-    )
+    hashtags.join(" ")
 }
 
 #[cfg(test)]
-mod tests {
-    use chrono::NaiveDate;
+pub(crate) mod tests {
     use super::*;
-    use utils::model::GameType;
+    use utils::model::{GameType, Price};
+
+    /// The game every platform test posts, rendered by `make_text`.
+    pub(crate) fn make_test_post(with_image: bool) -> Post {
+        let game = Game {
+            id: "unique_id".to_string(),
+            store: GameStore::Steam,
+            title: "The Game".to_string(),
+            identifier: "The_Game".to_string(),
+            url: "https://icudev.xyz/the_game".to_string(),
+            original_price: Some(Price::new(1999, "USD")),
+            offer_from: None,
+            offer_until: NaiveDate::from_ymd_opt(2025, 6, 15).unwrap(),
+            game_type: GameType::Game,
+            redeem_on: None,
+            offer_kind: OfferKind::Keep,
+            image_url: Some("https://icudev.xyz/the_game.png".to_string()),
+        };
+
+        Post {
+            text: make_text(&game),
+            image: with_image.then(|| PostImage {
+                image: Arc::new(Image {
+                    data: vec![0x89, 0x50, 0x4e, 0x47],
+                    content_type: "image/png".to_string(),
+                }),
+                alt_text: "Store artwork of \"The Game\"".to_string(),
+            }),
+            game,
+            kind: PostKind::Free,
+        }
+    }
 
     #[test]
     fn test_game() {
@@ -49,9 +302,13 @@ mod tests {
                 title: "The Game".to_string(),
                 identifier: "The_Game".to_string(),
                 url: "https://icudev.xyz/the_game".to_string(),
-                original_price: "$19.99".to_string(),
+                original_price: Some(Price::new(1999, "USD")),
+                offer_from: None,
                 offer_until: NaiveDate::from_ymd_opt(2025, 6, 15).unwrap(),
                 game_type: GameType::Game,
+                redeem_on: None,
+                offer_kind: OfferKind::Keep,
+                image_url: None,
             }),
             r#"[ Game ] "The Game" is currently free on #Steam until 2025-06-15.
 
@@ -70,9 +327,13 @@ https://icudev.xyz/the_game
                 title: "The Game: The DLC".to_string(),
                 identifier: "The_Game_The_DLC".to_string(),
                 url: "https://icudev.xyz/the_game_the_dlc".to_string(),
-                original_price: "$9.99".to_string(),
+                original_price: Some(Price::new(999, "USD")),
+                offer_from: None,
                 offer_until: NaiveDate::from_ymd_opt(2025, 6, 15).unwrap(),
                 game_type: GameType::Dlc,
+                redeem_on: None,
+                offer_kind: OfferKind::Keep,
+                image_url: None,
             }),
             r#"[ DLC ] "The Game: The DLC" is currently free on #Steam until 2025-06-15.
 
@@ -81,4 +342,147 @@ https://icudev.xyz/the_game_the_dlc
 #FreeGames #The_Game_The_DLC #SteamDeals"#
         );
     }
+
+    #[test]
+    fn test_redeem_on() {
+        assert_eq!(
+            make_text(&Game {
+                id: "unique_prime_id".to_string(),
+                store: GameStore::PrimeGaming,
+                title: "The Game".to_string(),
+                identifier: "The_Game".to_string(),
+                url: "https://icudev.xyz/the_game".to_string(),
+                original_price: None,
+                offer_from: None,
+                offer_until: NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+                game_type: GameType::Game,
+                redeem_on: Some(Launcher::Gog),
+                offer_kind: OfferKind::Keep,
+                image_url: None,
+            }),
+            r#"[ Game ] "The Game" is currently free on #PrimeGaming until 2025-06-30. Redeem it on GOG.
+
+https://icudev.xyz/the_game
+
+#FreeGames #The_Game #PrimeGaming"#
+        );
+    }
+
+    #[test]
+    fn test_temporary_play() {
+        assert_eq!(
+            make_text(&Game {
+                id: "unique_weekend_id".to_string(),
+                store: GameStore::Steam,
+                title: "The Game".to_string(),
+                identifier: "The_Game".to_string(),
+                url: "https://icudev.xyz/the_game".to_string(),
+                original_price: Some(Price::new(2999, "USD")),
+                offer_from: None,
+                offer_until: NaiveDate::from_ymd_opt(2025, 6, 23).unwrap(),
+                game_type: GameType::Game,
+                redeem_on: None,
+                offer_kind: OfferKind::TemporaryPlay,
+                image_url: None,
+            }),
+            r#"[ Game ] "The Game" can be played for free on #Steam until 2025-06-23, but you can't keep it afterwards.
+
+https://icudev.xyz/the_game
+
+#FreeGames #The_Game #SteamDeals"#
+        );
+    }
+
+    #[test]
+    fn test_free_to_play() {
+        assert_eq!(
+            make_text(&Game {
+                id: "unique_launch_id".to_string(),
+                store: GameStore::Steam,
+                title: "The Game".to_string(),
+                identifier: "The_Game".to_string(),
+                url: "https://icudev.xyz/the_game".to_string(),
+                original_price: None,
+                offer_from: None,
+                offer_until: NaiveDate::from_ymd_opt(2025, 6, 19).unwrap(),
+                game_type: GameType::Game,
+                redeem_on: None,
+                offer_kind: OfferKind::FreeToPlay,
+                image_url: None,
+            }),
+            r#"[ Game ] "The Game" just launched as free-to-play on #Steam.
+
+https://icudev.xyz/the_game
+
+#FreeGames #The_Game #SteamDeals"#
+        );
+    }
+
+    #[test]
+    fn test_deliveries() {
+        let deliveries = Deliveries::default();
+        let post = make_test_post(false);
+
+        deliveries.delivered(&post, "first");
+        assert!(deliveries.is_delivered(&post, "first"));
+        assert!(!deliveries.is_delivered(&post, "second"));
+
+        deliveries.forget(&post);
+        assert!(!deliveries.is_delivered(&post, "first"));
+    }
+
+    #[test]
+    fn test_upcoming() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 5).unwrap();
+
+        assert_eq!(
+            make_upcoming_text(&Game {
+                id: "unique_upcoming_id".to_string(),
+                store: GameStore::EpicGames,
+                title: "Mystery Game".to_string(),
+                identifier: "Mystery_Game".to_string(),
+                url: "https://icudev.xyz/free-games".to_string(),
+                original_price: None,
+                offer_from: NaiveDate::from_ymd_opt(2025, 6, 12),
+                offer_until: NaiveDate::from_ymd_opt(2025, 6, 19).unwrap(),
+                game_type: GameType::Game,
+                redeem_on: None,
+                offer_kind: OfferKind::Keep,
+                image_url: None,
+            }, today),
+            r#"[ Coming next week ] "Mystery Game" will be free on #EpicGames starting 2025-06-12.
+
+https://icudev.xyz/free-games
+
+#FreeGames #Mystery_Game"#
+        );
+    }
+
+    #[test]
+    fn test_lead_in() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 12).unwrap();
+        let lead_in = |day| make_lead_in(NaiveDate::from_ymd_opt(2025, 6, day).unwrap(), today);
+
+        assert_eq!(lead_in(12), "Starting today");
+        assert_eq!(lead_in(13), "Coming tomorrow");
+        assert_eq!(lead_in(16), "Coming in 4 days");
+        assert_eq!(lead_in(19), "Coming next week");
+        assert_eq!(lead_in(30), "Coming soon");
+    }
+
+    #[test]
+    fn test_shorten_text() {
+        let text = "[ Game ] \"The Game\" is currently free on #Steam until 2025-06-15.\n\nhttps://icudev.xyz/the_game\n\n#FreeGames #The_Game #SteamDeals";
+        let length = |text: &str| text.chars().count();
+
+        assert_eq!(shorten_text(text, 200, length), text);
+        assert_eq!(
+            shorten_text(text, 117, length),
+            "[ Game ] \"The Game\" is currently free on #Steam until 2025-06-15.\n\nhttps://icudev.xyz/the_game\n\n#FreeGames #The_Game",
+        );
+        assert_eq!(
+            shorten_text(text, 60, length),
+            "[ Game ] \"The Game\" is…\n\nhttps://icudev.xyz/the_game",
+        );
+    }
 }
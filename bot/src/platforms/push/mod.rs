@@ -0,0 +1,242 @@
+mod model;
+
+use std::env;
+use std::error::Error;
+use reqwest::Client;
+use serde_json::json;
+use utils::model::{GameType, OfferKind, PostKind};
+use crate::platforms::{Platform, Post};
+
+const NTFY_DEFAULT_SERVER_URL: &str = "https://ntfy.sh";
+
+/// Publishes games as phone notifications to an ntfy topic.
+pub struct NtfyPlatform {
+    http: Client,
+    server_url: String,
+    topic: String,
+    access_token: Option<String>,
+}
+
+impl NtfyPlatform {
+    pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        let server_url = env::var("NTFY_SERVER_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or(NTFY_DEFAULT_SERVER_URL.to_string());
+
+        Ok(Self::with_server(
+            server_url,
+            env::var("NTFY_TOPIC")?,
+            env::var("NTFY_ACCESS_TOKEN").ok().filter(|token| !token.is_empty()),
+        ))
+    }
+
+    pub(crate) fn with_server(server_url: String, topic: String, access_token: Option<String>) -> Self {
+        Self {
+            http: Client::new(),
+            server_url: server_url.trim_end_matches('/').to_string(),
+            topic,
+            access_token,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Platform for NtfyPlatform {
+    fn name(&self) -> String {
+        String::from("Ntfy")
+    }
+
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        let mut request = self.http.post(&self.server_url)
+            .json(&model::NtfyMessage {
+                topic: self.topic.clone(),
+                title: post.game.title.clone(),
+                message: headline(post).to_string(),
+                priority: priority(post),
+                tags: make_tags(post),
+                click: post.game.url.clone(),
+                attach: post.game.image_url.clone(),
+            });
+        if let Some(ref access_token) = self.access_token {
+            request = request.bearer_auth(access_token);
+        }
+
+        request.send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Publishes games as notifications to a Gotify application.
+pub struct GotifyPlatform {
+    http: Client,
+    server_url: String,
+    app_token: String,
+}
+
+impl GotifyPlatform {
+    pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(Self::with_server(env::var("GOTIFY_SERVER_URL")?, env::var("GOTIFY_APP_TOKEN")?))
+    }
+
+    pub(crate) fn with_server(server_url: String, app_token: String) -> Self {
+        Self {
+            http: Client::new(),
+            server_url: server_url.trim_end_matches('/').to_string(),
+            app_token,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Platform for GotifyPlatform {
+    fn name(&self) -> String {
+        String::from("Gotify")
+    }
+
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        self.http.post(format!("{}/message", self.server_url))
+            .header("X-Gotify-Key", &self.app_token)
+            .json(&model::GotifyMessage {
+                title: post.game.title.clone(),
+                message: headline(post).to_string(),
+                priority: priority(post),
+                extras: json!({
+                    "client::notification": {
+                        "click": { "url": post.game.url },
+                    },
+                }),
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+fn headline(post: &Post) -> &str {
+    post.text.split("\n\n").next().unwrap_or_default()
+}
+
+/// Games that can be kept are worth a louder notification than the rest, free-to-play launches
+/// cost nothing to begin with and get a quieter one.
+fn priority(post: &Post) -> u8 {
+    match (&post.kind, &post.game.offer_kind) {
+        (PostKind::Free, OfferKind::Keep) => 4,
+        (PostKind::Free, OfferKind::FreeToPlay) => 2,
+        _ => 3,
+    }
+}
+
+/// The first tag is an emoji shortcode, which ntfy shows in front of the title.
+fn make_tags(post: &Post) -> Vec<String> {
+    let emoji = match (&post.kind, &post.game.game_type) {
+        (PostKind::Upcoming, _) => "hourglass_flowing_sand",
+        (PostKind::Free, GameType::Dlc) => "jigsaw",
+        (PostKind::Free, GameType::Software) => "computer",
+        (PostKind::Free, GameType::Bundle) => "package",
+        (PostKind::Free, _) => "video_game",
+    };
+
+    vec![
+        emoji.to_string(),
+        post.game.store.to_string().to_lowercase(),
+        post.game.game_type.to_string().to_lowercase(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use crate::platforms::tests::make_test_post;
+    use super::*;
+
+    #[test]
+    fn test_make_tags() {
+        let mut post = make_test_post(false);
+        assert_eq!(make_tags(&post), vec!["video_game", "steam", "game"]);
+
+        post.kind = PostKind::Upcoming;
+        post.game.game_type = GameType::Dlc;
+        assert_eq!(make_tags(&post), vec!["hourglass_flowing_sand", "steam", "dlc"]);
+    }
+
+    #[test]
+    fn test_priority() {
+        let mut post = make_test_post(false);
+        assert_eq!(priority(&post), 4);
+
+        post.game.offer_kind = OfferKind::TemporaryPlay;
+        assert_eq!(priority(&post), 3);
+
+        post.game.offer_kind = OfferKind::FreeToPlay;
+        assert_eq!(priority(&post), 2);
+    }
+
+    #[tokio::test]
+    async fn test_post_ntfy() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/"))
+            .and(header("Authorization", "Bearer token"))
+            .and(body_partial_json(json!({
+                "topic": "freegames",
+                "title": "The Game",
+                "message": "[ Game ] \"The Game\" is currently free on #Steam until 2025-06-15.",
+                "priority": 4,
+                "tags": ["video_game", "steam", "game"],
+                "click": "https://icudev.xyz/the_game",
+                "attach": "https://icudev.xyz/the_game.png",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "1", "event": "message" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = NtfyPlatform::with_server(server.uri(), "freegames".to_string(), Some("token".to_string()));
+
+        platform.post(&make_test_post(false)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_gotify() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/message"))
+            .and(header("X-Gotify-Key", "app-token"))
+            .and(body_partial_json(json!({
+                "title": "The Game",
+                "priority": 4,
+                "extras": { "client::notification": { "click": { "url": "https://icudev.xyz/the_game" } } },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 1 })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = GotifyPlatform::with_server(server.uri(), "app-token".to_string());
+
+        platform.post(&make_test_post(false)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_fails_on_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+
+        let platform = NtfyPlatform::with_server(server.uri(), "freegames".to_string(), None);
+
+        assert!(platform.post(&make_test_post(false)).await.is_err());
+    }
+}
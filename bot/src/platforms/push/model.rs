@@ -0,0 +1,23 @@
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Serialize, Debug)]
+pub struct NtfyMessage {
+    pub topic: String,
+    pub title: String,
+    pub message: String,
+    pub priority: u8,
+    pub tags: Vec<String>,
+    pub click: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GotifyMessage {
+    pub title: String,
+    pub message: String,
+    pub priority: u8,
+    // Client settings are namespaced keys like `client::notification`
+    pub extras: Value,
+}
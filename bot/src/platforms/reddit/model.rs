@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Deserialize, Debug)]
+pub struct AccessToken {
+    pub access_token: String,
+    pub expires_in: u64,
+    // pub token_type: String,
+    // pub scope: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Submission {
+    pub api_type: String,
+    pub sr: String,
+    pub kind: String,
+    pub title: String,
+    pub url: String,
+    // Offers can come back for the same store page, which is still a new post
+    pub resubmit: bool,
+    pub sendreplies: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flair_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SubmitResponse {
+    pub json: SubmitResult,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SubmitResult {
+    // Each error is a list of the error code, a message and the affected field
+    pub errors: Vec<Vec<Value>>,
+}
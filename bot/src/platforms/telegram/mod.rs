@@ -0,0 +1,282 @@
+mod model;
+
+use std::env;
+use std::error::Error;
+use reqwest::{Client, Response};
+use reqwest::multipart::{Form, Part};
+use crate::platforms::{escape_html, split_list, Deliveries, Platform, Post, PostImage};
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+// Photo captions are a lot shorter than messages, longer posts are sent as message instead
+const TELEGRAM_MAX_CAPTION_LENGTH: usize = 1024;
+
+pub struct TelegramPlatform {
+    http: Client,
+    api_url: String,
+    bot_token: String,
+    chat_ids: Vec<String>,
+    deliveries: Deliveries,
+}
+
+impl TelegramPlatform {
+    pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        let chat_ids = split_list(&env::var("TELEGRAM_CHAT_IDS")?).collect::<Vec<_>>();
+        if chat_ids.is_empty() {
+            return Err("TELEGRAM_CHAT_IDS is empty".into());
+        }
+
+        Ok(Self::with_chats(TELEGRAM_API_URL.to_string(), env::var("TELEGRAM_BOT_TOKEN")?, chat_ids))
+    }
+
+    pub(crate) fn with_chats(api_url: String, bot_token: String, chat_ids: Vec<String>) -> Self {
+        Self {
+            http: Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            bot_token,
+            chat_ids,
+            deliveries: Deliveries::default(),
+        }
+    }
+
+    fn method_url(&self, method: &str) -> String {
+        format!("{}/bot{}/{method}", self.api_url, self.bot_token)
+    }
+
+    async fn send_message(&self, chat_id: &str, text: String, reply_markup: model::InlineKeyboardMarkup) -> Result<(), Box<dyn Error>> {
+        let res = self.http.post(self.method_url("sendMessage"))
+            .json(&model::SendMessage {
+                chat_id: chat_id.to_string(),
+                text,
+                parse_mode: "HTML".to_string(),
+                reply_markup,
+            })
+            .send()
+            .await
+            .map_err(reqwest::Error::without_url)?;
+
+        check_response(res).await
+    }
+
+    async fn send_photo(&self, chat_id: &str, image: &PostImage, caption: String, reply_markup: model::InlineKeyboardMarkup) -> Result<(), Box<dyn Error>> {
+        let part = Part::bytes(image.image.data.clone())
+            .file_name("image")
+            .mime_str(&image.image.content_type)?;
+        let form = Form::new()
+            .text("chat_id", chat_id.to_string())
+            .text("caption", caption)
+            .text("parse_mode", "HTML")
+            // Multipart fields are plain strings, so the keyboard is sent as JSON
+            .text("reply_markup", serde_json::to_string(&reply_markup)?)
+            .part("photo", part);
+
+        let res = self.http.post(self.method_url("sendPhoto"))
+            .multipart(form)
+            .send()
+            .await
+            .map_err(reqwest::Error::without_url)?;
+
+        check_response(res).await
+    }
+}
+
+#[async_trait::async_trait]
+impl Platform for TelegramPlatform {
+    fn name(&self) -> String {
+        String::from("Telegram")
+    }
+
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        let text = make_html(post);
+
+        let mut failed = 0;
+        for chat_id in &self.chat_ids {
+            if self.deliveries.is_delivered(post, chat_id) {
+                continue;
+            }
+
+            let res = match post.image {
+                Some(ref image) if text.chars().count() <= TELEGRAM_MAX_CAPTION_LENGTH => {
+                    self.send_photo(chat_id, image, text.clone(), make_claim_button(post)).await
+                },
+                _ => self.send_message(chat_id, text.clone(), make_claim_button(post)).await,
+            };
+
+            match res {
+                Ok(()) => self.deliveries.delivered(post, chat_id),
+                Err(e) => {
+                    log::error!("Couldn't post to Telegram chat {chat_id}: {e}");
+                    failed += 1;
+                },
+            }
+        }
+
+        if failed > 0 {
+            return Err(format!("{failed} of {} Telegram chats failed", self.chat_ids.len()).into());
+        }
+
+        self.deliveries.forget(post);
+        Ok(())
+    }
+}
+
+async fn check_response(res: Response) -> Result<(), Box<dyn Error>> {
+    let status = res.status();
+    let api_response = res.json::<model::ApiResponse>()
+        .await
+        .map_err(reqwest::Error::without_url)?;
+
+    if !api_response.ok {
+        let description = api_response.description.unwrap_or(status.to_string());
+        return Err(format!("Telegram API error: {description}").into());
+    }
+
+    Ok(())
+}
+
+/// The post text with the game's title in bold, the link itself is replaced by the claim button.
+fn make_html(post: &Post) -> String {
+    let quoted_title = escape_html(&format!("\"{}\"", post.game.title));
+    let bold_title = format!("<b>{}</b>", escape_html(&post.game.title));
+
+    post.text.split("\n\n")
+        .filter(|paragraph| *paragraph != post.game.url)
+        .map(|paragraph| escape_html(paragraph).replacen(&quoted_title, &bold_title, 1))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn make_claim_button(post: &Post) -> model::InlineKeyboardMarkup {
+    model::InlineKeyboardMarkup {
+        inline_keyboard: vec![vec![model::InlineKeyboardButton {
+            text: "Claim".to_string(),
+            url: post.game.url.clone(),
+        }]],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+    use wiremock::matchers::{body_partial_json, method, path};
+    use crate::platforms::tests::make_test_post;
+    use super::*;
+
+    const HTML: &str = "[ Game ] <b>The Game</b> is currently free on #Steam until 2025-06-15.\n\n#FreeGames #The_Game #SteamDeals";
+
+    #[test]
+    fn test_make_html() {
+        let mut post = make_test_post(false);
+        assert_eq!(make_html(&post), HTML);
+
+        post.game.title = "Tom & Jerry <Remastered>".to_string();
+        post.text = "[ Game ] \"Tom & Jerry <Remastered>\" is currently free on #Steam until 2025-06-15.".to_string();
+        assert_eq!(
+            make_html(&post),
+            "[ Game ] <b>Tom &amp; Jerry &lt;Remastered&gt;</b> is currently free on #Steam until 2025-06-15.",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_message() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .and(body_partial_json(json!({
+                "chat_id": "@freegames",
+                "text": HTML,
+                "parse_mode": "HTML",
+                "reply_markup": { "inline_keyboard": [[{ "text": "Claim", "url": "https://icudev.xyz/the_game" }]] },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true, "result": {} })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = TelegramPlatform::with_chats(server.uri(), "token".to_string(), vec!["@freegames".to_string()]);
+
+        platform.post(&make_test_post(false)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_photo() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendPhoto"))
+            // The image makes the body invalid UTF-8, which `body_string_contains` can't match
+            .and(|request: &Request| {
+                let body = String::from_utf8_lossy(&request.body);
+                body.contains("<b>The Game</b>")
+                    && body.contains(r#"{"inline_keyboard":[[{"text":"Claim","url":"https://icudev.xyz/the_game"}]]}"#)
+            })
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true, "result": {} })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let platform = TelegramPlatform::with_chats(server.uri(), "token".to_string(), vec!["@freegames".to_string(), "-1001234".to_string()]);
+
+        platform.post(&make_test_post(true)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_fails_on_api_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "ok": false,
+                "error_code": 400,
+                "description": "Bad Request: chat not found",
+            })))
+            .mount(&server)
+            .await;
+
+        let platform = TelegramPlatform::with_chats(server.uri(), "token".to_string(), vec!["@freegames".to_string()]);
+
+        assert!(platform.post(&make_test_post(false)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_post_retries_failed_chats_only() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .and(body_partial_json(json!({ "chat_id": "@freegames" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true, "result": {} })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .and(body_partial_json(json!({ "chat_id": "-1001234" })))
+            .respond_with(ResponseTemplate::new(429).set_body_json(json!({
+                "ok": false,
+                "error_code": 429,
+                "description": "Too Many Requests: retry after 1",
+            })))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/bottoken/sendMessage"))
+            .and(body_partial_json(json!({ "chat_id": "-1001234" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true, "result": {} })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = TelegramPlatform::with_chats(server.uri(), "token".to_string(), vec!["@freegames".to_string(), "-1001234".to_string()]);
+        let post = make_test_post(false);
+
+        assert!(platform.post(&post).await.is_err());
+        platform.post(&post).await.unwrap();
+    }
+}
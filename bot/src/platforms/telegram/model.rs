@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Debug)]
+pub struct SendMessage {
+    pub chat_id: String,
+    pub text: String,
+    pub parse_mode: String,
+    pub reply_markup: InlineKeyboardMarkup,
+}
+
+#[derive(Serialize, Debug)]
+pub struct InlineKeyboardMarkup {
+    pub inline_keyboard: Vec<Vec<InlineKeyboardButton>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct InlineKeyboardButton {
+    pub text: String,
+    pub url: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApiResponse {
+    pub ok: bool,
+    pub description: Option<String>,
+    // pub result: serde_json::Value,
+}
@@ -1,25 +1,75 @@
+mod oauth;
+
 use std::error::Error;
 use std::env;
+use reqwest::Client;
+use reqwest::header::AUTHORIZATION;
+use reqwest::multipart::{Form, Part};
+use serde_json::{json, Value};
 use twitter_v2::authorization::Oauth1aToken;
 use twitter_v2::TwitterApi;
-use utils::model::Game;
-use crate::platforms::{make_text, Platform};
+use crate::platforms::{Platform, Post, PostImage};
+use crate::platforms::twitter::oauth::Oauth1Credentials;
+
+// Media uploads are not part of the v2 API client, so they are done against v1.1 directly
+const TWITTER_MEDIA_UPLOAD_URL: &str = "https://upload.twitter.com/1.1/media/upload.json";
+const TWITTER_MEDIA_METADATA_URL: &str = "https://upload.twitter.com/1.1/media/metadata/create.json";
 
 pub struct TwitterPlatform {
     client: TwitterApi<Oauth1aToken>,
+    http: Client,
+    credentials: Oauth1Credentials,
 }
 
 impl TwitterPlatform {
     pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        let credentials = Oauth1Credentials {
+            consumer_key: env::var("TWITTER_API_KEY")?,
+            consumer_secret: env::var("TWITTER_API_KEY_SECRET")?,
+            token: env::var("TWITTER_ACCESS_TOKEN")?,
+            token_secret: env::var("TWITTER_ACCESS_TOKEN_SECRET")?,
+        };
+
         Ok(Self {
             client: TwitterApi::new(Oauth1aToken::new(
-                env::var("TWITTER_API_KEY")?,
-                env::var("TWITTER_API_KEY_SECRET")?,
-                env::var("TWITTER_ACCESS_TOKEN")?,
-                env::var("TWITTER_ACCESS_TOKEN_SECRET")?,
-            ))
+                credentials.consumer_key.clone(),
+                credentials.consumer_secret.clone(),
+                credentials.token.clone(),
+                credentials.token_secret.clone(),
+            )),
+            http: Client::new(),
+            credentials,
         })
     }
+
+    async fn upload_image(&self, image: &PostImage) -> Result<u64, Box<dyn Error>> {
+        let part = Part::bytes(image.image.data.clone()).mime_str(&image.image.content_type)?;
+        let form = Form::new().part("media", part);
+        let res = self.http.post(TWITTER_MEDIA_UPLOAD_URL)
+            .header(AUTHORIZATION, self.credentials.authorization_header("POST", TWITTER_MEDIA_UPLOAD_URL, &[]))
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Value>()
+            .await?;
+
+        let Some(media_id) = res["media_id"].as_u64() else {
+            return Err("media upload response has no media_id".into());
+        };
+
+        self.http.post(TWITTER_MEDIA_METADATA_URL)
+            .header(AUTHORIZATION, self.credentials.authorization_header("POST", TWITTER_MEDIA_METADATA_URL, &[]))
+            .json(&json!({
+                "media_id": media_id.to_string(),
+                "alt_text": { "text": image.alt_text },
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(media_id)
+    }
 }
 
 #[async_trait::async_trait]
@@ -28,12 +78,20 @@ impl Platform for TwitterPlatform {
         String::from("Twitter")
     }
 
-    async fn post_game(&self, game: &Game) -> Result<(), Box<dyn Error>> {
-        let res = self.client
-            .post_tweet()
-            .text(make_text(game))
-            .send()
-            .await;
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        let mut tweet = self.client.post_tweet();
+        tweet.text(post.text.clone());
+
+        if let Some(ref image) = post.image {
+            match self.upload_image(image).await {
+                Ok(media_id) => {
+                    tweet.add_media([media_id], Vec::<u64>::new());
+                },
+                Err(e) => log::error!("Couldn't upload image to Twitter: {e}"),
+            }
+        }
+
+        let res = tweet.send().await;
 
         match res {
             Ok(_) => Ok(()),
@@ -0,0 +1,101 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use sha1::Sha1;
+
+// Everything except the unreserved characters of RFC 3986 has to be encoded
+const OAUTH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// OAuth 1.0a user context credentials, used for the endpoints `twitter-v2` doesn't cover.
+pub(crate) struct Oauth1Credentials {
+    pub consumer_key: String,
+    pub consumer_secret: String,
+    pub token: String,
+    pub token_secret: String,
+}
+
+impl Oauth1Credentials {
+    /// Builds the `Authorization` header for a request. `params` are the query or form parameters
+    /// of the request, multipart bodies are not part of the signature.
+    pub fn authorization_header(&self, method: &str, url: &str, params: &[(&str, &str)]) -> String {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        let nonce = format!("{:x}", now.as_nanos());
+        let timestamp = now.as_secs().to_string();
+
+        self.sign(method, url, params, &nonce, &timestamp)
+    }
+
+    fn sign(&self, method: &str, url: &str, params: &[(&str, &str)], nonce: &str, timestamp: &str) -> String {
+        let mut oauth_params = vec![
+            ("oauth_consumer_key", self.consumer_key.as_str()),
+            ("oauth_nonce", nonce),
+            ("oauth_signature_method", "HMAC-SHA1"),
+            ("oauth_timestamp", timestamp),
+            ("oauth_token", self.token.as_str()),
+            ("oauth_version", "1.0"),
+        ];
+
+        let mut signature_params = oauth_params.iter()
+            .chain(params.iter())
+            .map(|(key, value)| (encode(key), encode(value)))
+            .collect::<Vec<_>>();
+        signature_params.sort();
+
+        let parameter_string = signature_params.iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        let base_string = format!("{}&{}&{}", method.to_uppercase(), encode(url), encode(&parameter_string));
+        let signing_key = format!("{}&{}", encode(&self.consumer_secret), encode(&self.token_secret));
+
+        let mut mac = Hmac::<Sha1>::new_from_slice(signing_key.as_bytes()).expect("HMAC accepts keys of any size");
+        mac.update(base_string.as_bytes());
+        let signature = STANDARD.encode(mac.finalize().into_bytes());
+
+        oauth_params.push(("oauth_signature", signature.as_str()));
+
+        let header = oauth_params.iter()
+            .map(|(key, value)| format!("{}=\"{}\"", encode(key), encode(value)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("OAuth {header}")
+    }
+}
+
+fn encode(value: &str) -> String {
+    utf8_percent_encode(value, OAUTH_ENCODE_SET).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example from https://developer.x.com/en/docs/authentication/oauth-1-0a/creating-a-signature
+    #[test]
+    fn test_signature() {
+        let credentials = Oauth1Credentials {
+            consumer_key: "xvz1evFS4wEEPTGEFPHBog".to_string(),
+            consumer_secret: "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw".to_string(),
+            token: "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb".to_string(),
+            token_secret: "LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE".to_string(),
+        };
+
+        let header = credentials.sign(
+            "POST",
+            "https://api.twitter.com/1.1/statuses/update.json",
+            &[("include_entities", "true"), ("status", "Hello Ladies + Gentlemen, a signed OAuth request!")],
+            "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg",
+            "1318622958",
+        );
+
+        assert!(header.starts_with("OAuth oauth_consumer_key=\"xvz1evFS4wEEPTGEFPHBog\", "));
+        assert!(header.ends_with("oauth_signature=\"hCtSmYh%2BiHYCEqBWrE7C7hYmtUk%3D\""));
+    }
+}
@@ -0,0 +1,270 @@
+use std::env;
+use std::error::Error;
+use std::time::Duration;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use sha2::Sha256;
+use utils::model::{Game, PostKind};
+use crate::platforms::{split_list, Deliveries, Platform, Post};
+
+const WEBHOOK_MAX_ATTEMPTS: u32 = 4;
+const WEBHOOK_BACKOFF: Duration = Duration::from_secs(2);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SIGNATURE_HEADER: &str = "X-FreeGames-Signature-256";
+const DELIVERY_HEADER: &str = "X-FreeGames-Delivery";
+
+#[derive(Serialize, Debug)]
+struct WebhookPayload<'a> {
+    kind: &'a PostKind,
+    text: &'a str,
+    game: &'a Game,
+}
+
+/// Posts every game as JSON to arbitrary URLs. The body is signed with HMAC-SHA256 like GitHub
+/// does, so receivers can verify it came from us.
+pub struct WebhookPlatform {
+    http: Client,
+    urls: Vec<String>,
+    secret: String,
+    backoff: Duration,
+    deliveries: Deliveries,
+}
+
+impl WebhookPlatform {
+    pub(crate) fn new() -> Result<Self, Box<dyn Error>> {
+        let urls = split_list(&env::var("WEBHOOK_URLS")?).collect::<Vec<_>>();
+        if urls.is_empty() {
+            return Err("WEBHOOK_URLS is empty".into());
+        }
+
+        Self::with_urls(urls, env::var("WEBHOOK_SECRET")?, WEBHOOK_BACKOFF)
+    }
+
+    pub(crate) fn with_urls(urls: Vec<String>, secret: String, backoff: Duration) -> Result<Self, Box<dyn Error>> {
+        if secret.is_empty() {
+            return Err("WEBHOOK_SECRET must not be empty".into());
+        }
+
+        Ok(Self {
+            http: Client::builder().timeout(WEBHOOK_TIMEOUT).build()?,
+            urls,
+            secret,
+            backoff,
+            deliveries: Deliveries::default(),
+        })
+    }
+
+    async fn deliver(&self, url: &str, delivery: &str, body: &[u8], signature: &str) -> Result<(), Box<dyn Error>> {
+        let mut attempt = 1;
+        loop {
+            let res = self.http.post(url)
+                .header(CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, signature)
+                .header(DELIVERY_HEADER, delivery)
+                .body(body.to_vec())
+                .send()
+                .await
+                .map_err(reqwest::Error::without_url);
+
+            let retryable = match res {
+                Ok(res) if res.status().is_success() => return Ok(()),
+                // Client errors won't go away by sending the same payload again
+                Ok(res) if res.status().is_client_error() && res.status().as_u16() != 429 => {
+                    return Err(format!("webhook responded with {}", res.status()).into());
+                },
+                Ok(res) => format!("webhook responded with {}", res.status()),
+                Err(e) => e.to_string(),
+            };
+
+            if attempt >= WEBHOOK_MAX_ATTEMPTS {
+                return Err(format!("{retryable}, giving up after {attempt} attempts").into());
+            }
+
+            log::debug!("Webhook delivery {delivery} failed ({retryable}), retrying.");
+            tokio::time::sleep(self.backoff * 2u32.pow(attempt - 1)).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Platform for WebhookPlatform {
+    fn name(&self) -> String {
+        String::from("Webhook")
+    }
+
+    async fn post(&self, post: &Post) -> Result<(), Box<dyn Error>> {
+        let body = serde_json::to_vec(&WebhookPayload {
+            kind: &post.kind,
+            text: &post.text,
+            game: &post.game,
+        })?;
+        let signature = sign(&self.secret, &body);
+        // Failed deliveries are sent again in the next loop, receivers can deduplicate with this
+        let delivery = format!("{}:{}:{}", post.game.store, post.game.id, post.kind);
+
+        let mut failed = 0;
+        for url in &self.urls {
+            if self.deliveries.is_delivered(post, url) {
+                continue;
+            }
+
+            match self.deliver(url, &delivery, &body, &signature).await {
+                Ok(()) => self.deliveries.delivered(post, url),
+                Err(e) => {
+                    log::error!("Couldn't deliver webhook {delivery}: {e}");
+                    failed += 1;
+                },
+            }
+        }
+
+        if failed > 0 {
+            return Err(format!("{failed} of {} webhooks failed", self.urls.len()).into());
+        }
+
+        self.deliveries.forget(post);
+        Ok(())
+    }
+}
+
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::{body_partial_json, header, header_exists, method, path};
+    use crate::platforms::tests::make_test_post;
+    use super::*;
+
+    fn make_platform(server: &MockServer) -> WebhookPlatform {
+        WebhookPlatform::with_urls(
+            vec![format!("{}/hook", server.uri())],
+            "secret".to_string(),
+            Duration::from_millis(10),
+        ).unwrap()
+    }
+
+    // Example from https://docs.github.com/en/webhooks/using-webhooks/validating-webhook-deliveries
+    #[test]
+    fn test_sign() {
+        assert_eq!(
+            sign("It's a Secret to Everybody", b"Hello, World!"),
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post() {
+        let server = MockServer::start().await;
+        let post = make_test_post(false);
+        let body = serde_json::to_vec(&WebhookPayload {
+            kind: &post.kind,
+            text: &post.text,
+            game: &post.game,
+        }).unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header(SIGNATURE_HEADER, sign("secret", &body).as_str()))
+            .and(header(DELIVERY_HEADER, "Steam:unique_id:Free"))
+            .and(body_partial_json(json!({
+                "kind": "Free",
+                "text": post.text,
+                "game": {
+                    "id": "unique_id",
+                    "store": "Steam",
+                    "title": "The Game",
+                    "url": "https://icudev.xyz/the_game",
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        make_platform(&server).post(&post).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_retries_server_errors() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header_exists(SIGNATURE_HEADER))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        make_platform(&server).post(&make_test_post(false)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_fails_on_client_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(410))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        assert!(make_platform(&server).post(&make_test_post(false)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_post_retries_failed_webhooks_only() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/other"))
+            .respond_with(ResponseTemplate::new(404))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/other"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let platform = WebhookPlatform::with_urls(
+            vec![format!("{}/hook", server.uri()), format!("{}/other", server.uri())],
+            "secret".to_string(),
+            Duration::from_millis(10),
+        ).unwrap();
+        let post = make_test_post(false);
+
+        assert!(platform.post(&post).await.is_err());
+        platform.post(&post).await.unwrap();
+    }
+}
@@ -1,6 +1,6 @@
 use reqwest::Client;
 use utils::internal_api::{wait_for_internal_api, InternalApi};
-use crate::stores::{EpicGamesStore, GogStore, SteamStore, Store};
+use crate::stores::{EpicGamesStore, GogStore, HumbleStore, ItchIoStore, PrimeGamingStore, SteamStore, Store};
 
 mod stores;
 
@@ -22,7 +22,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let stores: Vec<Box<dyn Store>> = vec![
         Box::new(EpicGamesStore),
         Box::new(GogStore),
-        Box::new(SteamStore),
+        Box::new(ItchIoStore),
+        Box::new(HumbleStore),
+        Box::new(PrimeGamingStore),
+        Box::new(SteamStore::new()),
     ];
 
     main_loop(&http, &stores, &internal_api).await;
@@ -35,12 +38,26 @@ async fn main_loop(http: &Client, stores: &Vec<Box<dyn Store>>, internal_api: &I
         log::debug!("Searching for games...");
 
         for store in stores.iter() {
-            let games = store.get_games(http, internal_api).await;
+            let report = match store.get_games(http, internal_api).await {
+                Ok(report) => report,
+                Err(e) => {
+                    log::error!("Scraping {} failed: {e}", store.name());
+                    continue;
+                }
+            };
 
-            for game in games {
+            if report.is_degraded() {
+                log::error!("Scraping {} found no games, the store layout might have changed: {report}", store.name());
+            } else {
+                log::info!("Scraped {}: {report}", store.name());
+            }
+
+            for game in report.games {
                 log::info!("Posting Game {:?} to API", game.title);
 
-                let _ = internal_api.post_game(&game).await;
+                if let Err(e) = internal_api.post_game(&game).await {
+                    log::error!("Couldn't post game {:?} to API: {e}", game.title);
+                }
 
                 tokio::time::sleep(std::time::Duration::from_secs(1)).await;
             }
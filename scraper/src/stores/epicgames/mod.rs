@@ -5,64 +5,31 @@ use chrono::NaiveDate;
 use reqwest::{Client, Url};
 use reqwest::header::HeaderMap;
 use utils::internal_api::InternalApi;
-use utils::model::{Game, GameStore, PartialGame};
-use crate::stores::{make_api_request, make_identifier, Store};
+use utils::model::{Game, GameStore, OfferKind, PartialGame, Price};
+use crate::stores::{make_api_request, make_identifier, ScrapeError, ScrapeReport, SkipReason, Store};
 
 pub struct EpicGamesStore;
 
 const EG_BASE_STORE_URL: &str = "https://store.epicgames.com/en-US/p";
+const EG_FREE_GAMES_URL: &str = "https://store.epicgames.com/en-US/free-games";
 const EG_API_URL: &str =
     "https://store-site-backend-static-ipv4.ak.epicgames.com/freeGamesPromotions";
 
 #[async_trait::async_trait]
 impl Store for EpicGamesStore {
-    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Vec<Game> {
+    fn name(&self) -> String {
+        String::from("EpicGames")
+    }
+
+    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Result<ScrapeReport, ScrapeError> {
         let url = Url::from_str(EG_API_URL).unwrap();
         let headers = HeaderMap::new();
         // let res = make_request(http, &url, headers).await;
 
-        let api_response = match make_api_request::<model::ApiResponse>(http, &url, headers).await {
-            Ok(api_response) => api_response,
-            Err(e) => {
-                log::error!("Error getting API response: {e}");
-                return vec![];
-            }
-        };
-
-        let eg_games = api_response.data.catalog.search_store.elements;
-
-        let mut free_games = Vec::with_capacity(eg_games.len());
-
-        for game in eg_games {
-            let Some(ref promotions) = game.promotions else {
-                continue;
-            };
-
-            if promotions.promotional_offers.is_empty() ||
-                promotions.promotional_offers[0].promotional_offers.is_empty()
-            {
-                continue;
-            }
-
-            let current_promotional_offer =
-                &promotions.promotional_offers[0].promotional_offers[0];
-
-            let discount_setting =
-                &current_promotional_offer.discount_setting;
-
-            let skip_if = [
-                // Game is not currently free
-                game.price.total_price.discount_price > 0,
-                // Game is free without discount
-                game.price.total_price.original_price == 0,
-                game.status.as_str() != "ACTIVE",
-                discount_setting.discount_percentage != 0,
-            ];
-
-            if skip_if.iter().any(|x| *x) {
-                continue;
-            }
+        let api_response = make_api_request::<model::ApiResponse>(http, &url, headers).await?;
+        let mut report = ScrapeReport::expecting_candidates();
 
+        for game in parse_games(api_response.data.catalog.search_store.elements, &mut report) {
             let partial_game = PartialGame {
                 id: game.id.clone(),
                 store: GameStore::EpicGames,
@@ -72,61 +39,228 @@ impl Store for EpicGamesStore {
                 Ok(false) => {
                     log::debug!("Game {} does not exist.", game.id)
                 },
-                _ => {
+                Ok(true) => {
                     log::debug!("Game {} already exists, skipping.", game.id);
+                    report.skip(SkipReason::AlreadyKnown);
+                    continue;
+                },
+                Err(e) => {
+                    log::error!("Couldn't check if game {} exists: {e}", game.id);
+                    report.skip(SkipReason::RequestFailed);
                     continue;
                 }
             }
 
-            // TODO: Improve without closure
-            let build_game_url = |path|
-                { format!("{EG_BASE_STORE_URL}/{path}") };
-            let mut game_url = None;
-            if let Some(mappings) = game.catalog_ns.mappings {
-                for mapping in mappings {
-                    if mapping.page_type.as_str() == "productHome" {
-                        game_url = Some(build_game_url(mapping.page_slug));
-                        break;
-                    }
+            report.games.push(game);
+        }
+
+        Ok(report)
+    }
+}
+
+/// Turns the promotions of the API response into games, current and upcoming giveaways alike.
+/// Whether we already know a game is up to the caller.
+fn parse_games(eg_games: Vec<model::EpicGamesGame>, report: &mut ScrapeReport) -> Vec<Game> {
+    let mut games = vec![];
+
+    for game in eg_games {
+        report.candidate();
+
+        let Some(ref promotions) = game.promotions else {
+            report.skip(SkipReason::NotFree);
+            continue;
+        };
+
+        let (current_promotional_offer, is_upcoming) =
+            match (first_offer(&promotions.promotional_offers), first_offer(&promotions.upcoming_promotional_offers)) {
+                (Some(offer), _) => (offer, false),
+                (None, Some(offer)) => (offer, true),
+                (None, None) => {
+                    report.skip(SkipReason::NotFree);
+                    continue;
+                },
+            };
+
+        let discount_setting =
+            &current_promotional_offer.discount_setting;
+
+        let skip_if = [
+            // Game is not currently free
+            !is_upcoming && game.price.total_price.discount_price > 0,
+            // Game is free without discount
+            game.price.total_price.original_price == 0 && !is_mystery_game(&game),
+            game.status.as_str() != "ACTIVE",
+            discount_setting.discount_percentage != 0,
+        ];
+
+        if skip_if.iter().any(|x| *x) {
+            report.skip(SkipReason::NotFree);
+            continue;
+        }
+
+        // TODO: Improve without closure
+        let build_game_url = |path|
+            { format!("{EG_BASE_STORE_URL}/{path}") };
+        let mut game_url = None;
+        if let Some(ref mappings) = game.catalog_ns.mappings {
+            for mapping in mappings {
+                if mapping.page_type.as_str() == "productHome" {
+                    game_url = Some(build_game_url(mapping.page_slug.clone()));
+                    break;
                 }
             }
-            if game_url.is_none() {
-                for attribute in game.custom_attributes {
-                    if attribute.key.as_str() == "com.epicgames.app.productSlug" {
-                        game_url = Some(build_game_url(attribute.value));
-                        break;
-                    }
+        }
+        if game_url.is_none() {
+            for attribute in &game.custom_attributes {
+                if attribute.key.as_str() == "com.epicgames.app.productSlug" {
+                    game_url = Some(build_game_url(attribute.value.clone()));
+                    break;
                 }
             }
-            let game_page_url = match game_url {
-                Some(url) => url,
-                None => continue,
-            };
+        }
+        let game_page_url = match game_url {
+            Some(url) => url,
+            // Mystery games don't have a product page until they are revealed
+            None if is_upcoming && is_mystery_game(&game) => EG_FREE_GAMES_URL.to_string(),
+            None => {
+                log::error!("Couldn't find product page of game {}", game.id);
+                report.skip(SkipReason::ParseFailed);
+                continue;
+            },
+        };
 
-            let offer_until = {
-                let offer_until_fmt = current_promotional_offer.end_date.clone();
-                let naive_data_string = match offer_until_fmt.get(0..10) {
-                    Some(date) => date,
-                    None => continue,
-                };
-                match NaiveDate::parse_from_str(naive_data_string, "%Y-%m-%d") {
-                    Ok(date) => date,
-                    Err(_) => continue,
-                }
+        let Some(offer_until) = parse_date(&current_promotional_offer.end_date) else {
+            log::error!("Couldn't parse end date of game {}", game.id);
+            report.skip(SkipReason::ParseFailed);
+            continue;
+        };
+
+        let offer_from = if is_upcoming {
+            let Some(offer_from) = parse_date(&current_promotional_offer.start_date) else {
+                log::error!("Couldn't parse start date of game {}", game.id);
+                report.skip(SkipReason::ParseFailed);
+                continue;
             };
+            Some(offer_from)
+        } else {
+            None
+        };
 
-            free_games.push(Game {
-                id: game.id,
-                store: GameStore::EpicGames,
-                title: game.title.clone(),
-                identifier: make_identifier(game.title.clone()),
-                url: game_page_url,
-                original_price: game.price.total_price.fmt_price.original_price.clone(),
-                offer_until,
-                game_type: game.offer_type,
-            })
-        }
+        // Once a mystery game is revealed it shows up under its real id, so the placeholder
+        // must not be listed as a free game after the giveaway started.
+        let offer_until = match offer_from {
+            Some(offer_from) if is_mystery_game(&game) => offer_from,
+            _ => offer_until,
+        };
+
+        games.push(Game {
+            id: game.id,
+            store: GameStore::EpicGames,
+            title: game.title.clone(),
+            identifier: make_identifier(game.title.clone()),
+            url: game_page_url,
+            // Epic already sends prices in the currency's minor unit
+            original_price: Some(Price::new(
+                i64::from(game.price.total_price.original_price),
+                &game.price.total_price.currency_code,
+            )),
+            offer_from,
+            offer_until,
+            game_type: game.offer_type,
+            redeem_on: None,
+            offer_kind: OfferKind::Keep,
+            image_url: get_key_image(&game.key_images),
+        })
+    }
+
+    games
+}
+
+fn first_offer(wrappers: &[model::PromotionalOfferWrapper]) -> Option<&model::PromotionalOffer> {
+    wrappers.first()?.promotional_offers.first()
+}
+
+/// Prefers the wide store images since they fit social media previews best.
+fn get_key_image(key_images: &[model::KeyImage]) -> Option<String> {
+    ["OfferImageWide", "DieselStoreFrontWide", "Thumbnail"].iter()
+        .find_map(|image_type| key_images.iter().find(|image| image.image_type.as_str() == *image_type))
+        .map(|image| image.url.clone())
+}
+
+fn is_mystery_game(game: &model::EpicGamesGame) -> bool {
+    game.title.to_lowercase().starts_with("mystery game")
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date.get(0..10)?, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::model::GameType;
+
+    const FREE_GAMES_PROMOTIONS_FIXTURE: &str =
+        include_str!("../../../fixtures/epicgames/free_games_promotions.json");
+
+    fn parse_fixture() -> (Vec<Game>, ScrapeReport) {
+        let api_response: model::ApiResponse = serde_json::from_str(FREE_GAMES_PROMOTIONS_FIXTURE).unwrap();
+        let mut report = ScrapeReport::default();
+        let games = parse_games(api_response.data.catalog.search_store.elements, &mut report);
+
+        (games, report)
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let (games, report) = parse_fixture();
+
+        assert_eq!(report.candidates, 5);
+        assert_eq!(report.skipped.get(&SkipReason::NotFree), Some(&2));
+        assert_eq!(games.iter().map(|game| game.id.as_str()).collect::<Vec<_>>(), vec![
+            "current0000000000000000000000000",
+            "upcoming000000000000000000000000",
+            "mystery0000000000000000000000000",
+        ]);
+    }
+
+    #[test]
+    fn test_parse_current_giveaway() {
+        let (games, _) = parse_fixture();
+        let game = &games[0];
+
+        assert_eq!(game.title, "The Game");
+        assert_eq!(game.url, "https://store.epicgames.com/en-US/p/the-game");
+        assert_eq!(game.original_price, Some(Price::new(1999, "USD")));
+        assert_eq!(game.offer_from, None);
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 19).unwrap());
+        assert!(matches!(game.game_type, GameType::Game));
+        assert_eq!(game.image_url.as_deref(), Some("https://cdn1.epicgames.com/offer/the-game/wide.jpg"));
+    }
+
+    #[test]
+    fn test_parse_upcoming_giveaway() {
+        let (games, _) = parse_fixture();
+        let game = &games[1];
+
+        assert_eq!(game.title, "Another Game");
+        // Upcoming games only have a product slug attribute, no page mapping yet
+        assert_eq!(game.url, "https://store.epicgames.com/en-US/p/another-game");
+        assert_eq!(game.offer_from, NaiveDate::from_ymd_opt(2025, 6, 19));
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 26).unwrap());
+        assert!(matches!(game.game_type, GameType::Dlc));
+    }
+
+    #[test]
+    fn test_parse_mystery_game() {
+        let (games, _) = parse_fixture();
+        let game = &games[2];
 
-        free_games
+        assert_eq!(game.title, "Mystery Game 2");
+        assert_eq!(game.url, EG_FREE_GAMES_URL);
+        assert_eq!(game.original_price, Some(Price::new(0, "USD")));
+        // The placeholder is only listed until the real game is revealed
+        assert_eq!(game.offer_from, NaiveDate::from_ymd_opt(2025, 6, 26));
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 26).unwrap());
     }
 }
@@ -38,7 +38,7 @@ pub(crate) struct EpicGamesGame {
     // pub viewable_date: String,
     pub status: String,
     // pub is_code_redemption_only: bool,
-    // pub key_images: Vec<KeyImage>,
+    pub key_images: Vec<KeyImage>,
     // pub seller: Seller,
     // pub product_slug: Option<String>,
     // pub url_slug: Option<String>,
@@ -66,12 +66,12 @@ where
     }
 }
 
-// #[derive(Debug, Deserialize)]
-// pub(crate) struct KeyImage {
-//     #[serde(rename = "type")]
-//     pub image_type: String,
-//     pub url: String,
-// }
+#[derive(Debug, Deserialize)]
+pub(crate) struct KeyImage {
+    #[serde(rename = "type")]
+    pub image_type: String,
+    pub url: String,
+}
 
 // #[derive(Debug, Deserialize)]
 // pub(crate) struct Seller {
@@ -127,18 +127,18 @@ pub(crate) struct TotalPrice {
     pub original_price: u16,
     // pub voucher_discount: u16,
     // pub discount: u16,
-    // pub currency_code: String,
+    pub currency_code: String,
     // pub currency_info: HashMap<String, u8>,
-    pub fmt_price: FmtPrice,
+    // pub fmt_price: FmtPrice,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct FmtPrice {
-    pub original_price: String,
-    // pub discount_price: String,
-    // pub intermediate_price: String,
-}
+// #[derive(Debug, Deserialize)]
+// #[serde(rename_all = "camelCase")]
+// pub(crate) struct FmtPrice {
+//     pub original_price: String,
+//     pub discount_price: String,
+//     pub intermediate_price: String,
+// }
 
 // #[derive(Debug, Deserialize)]
 // #[serde(rename_all = "camelCase")]
@@ -158,7 +158,8 @@ pub(crate) struct FmtPrice {
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Promotions {
     pub promotional_offers: Vec<PromotionalOfferWrapper>,
-    // pub upcoming_promotional_offers: Option<Vec<PromotionalOfferWrapper>>
+    #[serde(default)]
+    pub upcoming_promotional_offers: Vec<PromotionalOfferWrapper>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -170,7 +171,7 @@ pub(crate) struct PromotionalOfferWrapper {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct PromotionalOffer {
-    // pub start_date: String,
+    pub start_date: String,
     pub end_date: String,
     pub discount_setting: DiscountSetting,
 }
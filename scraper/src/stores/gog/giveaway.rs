@@ -0,0 +1,157 @@
+use std::str::FromStr;
+use chrono::DateTime;
+use regex::Regex;
+use reqwest::{Client, Url};
+use reqwest::header::{HeaderMap, COOKIE};
+use scraper::{Html, Selector};
+use utils::model::{Game, GameStore, GameType, OfferKind, Price};
+use crate::stores::{make_identifier, make_request, SkipReason};
+use super::COOKIES;
+
+const GOG_HOMEPAGE_URL: &str = "https://www.gog.com/en";
+
+/// The giveaway banner only links to the product page, the id, title and price have to be
+/// looked up there.
+pub(super) async fn get_giveaway(http: &Client) -> Result<Option<Game>, SkipReason> {
+    let homepage_url = Url::from_str(GOG_HOMEPAGE_URL).unwrap();
+    let homepage = fetch_page(http, &homepage_url).await?;
+
+    let Some(banner) = parse_giveaway_banner(&homepage)? else {
+        return Ok(None);
+    };
+
+    let Ok(product_url) = Url::from_str(&banner.url) else {
+        log::error!("Invalid giveaway url {}", banner.url);
+        return Err(SkipReason::ParseFailed);
+    };
+    let product_page = fetch_page(http, &product_url).await?;
+
+    match parse_product_page(&product_page, banner) {
+        Some(game) => Ok(Some(game)),
+        None => {
+            log::error!("Couldn't parse giveaway product page {product_url}");
+            Err(SkipReason::ParseFailed)
+        }
+    }
+}
+
+async fn fetch_page(http: &Client, url: &Url) -> Result<String, SkipReason> {
+    let mut headers = HeaderMap::new();
+    headers.append(COOKIE, COOKIES.parse().unwrap());
+
+    match make_request(http, url, headers).await {
+        Ok(response) => response.text().await.map_err(|_| SkipReason::RequestFailed),
+        Err(e) => {
+            log::error!("Error getting page {}: {e}", url.as_str());
+            Err(SkipReason::RequestFailed)
+        }
+    }
+}
+
+struct GiveawayBanner {
+    url: String,
+    image_url: Option<String>,
+    offer_until: chrono::NaiveDate,
+}
+
+/// Returns `None` if no giveaway is running, a banner we can't read is a parse failure.
+fn parse_giveaway_banner(html: &str) -> Result<Option<GiveawayBanner>, SkipReason> {
+    let banner_selector = Selector::parse(r#"a[id="giveaway"]"#).unwrap();
+    let countdown_selector = Selector::parse(r#"gog-countdown-timer"#).unwrap();
+    let image_selector = Selector::parse(r#"picture img"#).unwrap();
+
+    let document = Html::parse_document(html);
+
+    let Some(banner) = document.select(&banner_selector).next() else {
+        return Ok(None);
+    };
+
+    let Some(url) = banner.attr("href") else {
+        log::error!("Giveaway banner has no link");
+        return Err(SkipReason::ParseFailed);
+    };
+    let image_url = banner.select(&image_selector)
+        .next()
+        .and_then(|image| image.attr("src"))
+        .map(str::to_string);
+    let offer_until = banner.select(&countdown_selector)
+        .next()
+        .and_then(|countdown| countdown.attr("end-date"))
+        .and_then(|end_date| end_date.parse().ok())
+        // The countdown counts down to a unix timestamp in milliseconds
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|end_date| end_date.date_naive());
+    let Some(offer_until) = offer_until else {
+        log::error!("Couldn't read the countdown of the giveaway banner");
+        return Err(SkipReason::ParseFailed);
+    };
+
+    Ok(Some(GiveawayBanner { url: url.to_string(), image_url, offer_until }))
+}
+
+fn parse_product_page(html: &str, banner: GiveawayBanner) -> Option<Game> {
+    let title_selector = Selector::parse(r#"h1.productcard-basics__title"#).unwrap();
+    let base_price_selector = Selector::parse(r#"span.product-actions-price__base-amount"#).unwrap();
+    let product_id_regex = Regex::new(r#"card-product="(\d+)""#).unwrap();
+
+    let document = Html::parse_document(html);
+
+    let id = product_id_regex.captures(html)?[1].to_string();
+    let title = document.select(&title_selector).next()?.inner_html().trim().to_string();
+    let base_price = document.select(&base_price_selector).next()?.inner_html().trim().parse().ok()?;
+
+    Some(Game {
+        id,
+        store: GameStore::Gog,
+        identifier: make_identifier(title.clone()),
+        title,
+        url: banner.url,
+        // The product page is requested with the US_USD locale cookie, so the amount is always in dollars
+        original_price: Some(Price::from_major(base_price, "USD")),
+        offer_from: None,
+        offer_until: banner.offer_until,
+        game_type: GameType::Game,
+        redeem_on: None,
+        offer_kind: OfferKind::Keep,
+        image_url: banner.image_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use super::*;
+
+    const HOMEPAGE_FIXTURE: &str = include_str!("../../../fixtures/gog/homepage.html");
+    const PRODUCT_PAGE_FIXTURE: &str = include_str!("../../../fixtures/gog/product_page.html");
+
+    #[test]
+    fn test_parse_giveaway() {
+        let banner = parse_giveaway_banner(HOMEPAGE_FIXTURE).unwrap().unwrap();
+        assert_eq!(banner.url, "https://www.gog.com/en/game/the_game");
+        assert_eq!(banner.offer_until, NaiveDate::from_ymd_opt(2025, 6, 20).unwrap());
+        assert_eq!(banner.image_url.as_deref(), Some("https://images.gog-statics.com/giveaway_banner.jpg"));
+
+        let game = parse_product_page(PRODUCT_PAGE_FIXTURE, banner).unwrap();
+        assert_eq!(game.id, "1207658924");
+        assert_eq!(game.title, "The Game");
+        assert_eq!(game.original_price, Some(Price::new(999, "USD")));
+    }
+
+    #[test]
+    fn test_pages_are_priced_in_dollars() {
+        assert!(COOKIES.split(';').any(|cookie| cookie == "gog_lc=US_USD_en-US"));
+    }
+
+    #[test]
+    fn test_no_giveaway() {
+        let html = HOMEPAGE_FIXTURE.replace(r#"id="giveaway""#, r#"id="promo""#);
+        assert!(matches!(parse_giveaway_banner(&html), Ok(None)));
+    }
+
+    #[test]
+    fn test_unreadable_countdown() {
+        let html = HOMEPAGE_FIXTURE.replace("end-date=", "data-end=");
+        assert!(matches!(parse_giveaway_banner(&html), Err(SkipReason::ParseFailed)));
+    }
+}
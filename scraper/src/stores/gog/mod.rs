@@ -1,41 +1,56 @@
+mod giveaway;
 mod model;
 
+use std::collections::HashSet;
 use std::str::FromStr;
 use chrono::NaiveDate;
 use regex::Regex;
 use reqwest::{Client, Url};
 use reqwest::header::{HeaderMap, COOKIE};
 use utils::internal_api::InternalApi;
-use utils::model::{Game, GameStore, PartialGame};
-use crate::stores::{make_api_request, make_identifier, make_request, Store};
+use utils::model::{Game, GameStore, OfferKind, PartialGame, Price};
+use crate::stores::{make_api_request, make_identifier, make_request, ScrapeError, ScrapeReport, SkipReason, Store};
 
 pub struct GogStore;
 
-const GOG_API_URL: &str = "https://catalog.gog.com/v1/catalog?limit=48&price=between:0,0&order=desc:trending&discounted=eq:true&productType=in:game,pack,dlc,extras&page=1&countryCode=US&locale=en-US&currencyCode=USD";
+const GOG_API_URL: &str = "https://catalog.gog.com/v1/catalog?limit=48&price=between:0,0&order=desc:trending&discounted=eq:true&productType=in:game,pack,dlc,extras&countryCode=US&locale=en-US&currencyCode=USD";
+// Upper bound so a misbehaving API can't keep us paging forever
+const GOG_MAX_PAGES: usize = 20;
 
-const COOKIES: &str = "gog_wantsmaturecontent=18;";
+// Without the locale cookie GOG prices its pages for the region of the requesting IP
+const COOKIES: &str = "gog_wantsmaturecontent=18;gog_lc=US_USD_en-US;";
 
 #[async_trait::async_trait]
 impl Store for GogStore {
-    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Vec<Game> {
-        let url = Url::from_str(GOG_API_URL).unwrap();
-        let api_response = match make_api_request::<model::ApiResponse>(http, &url, HeaderMap::new()).await {
-            Ok(response) => response,
-            Err(e) => {
-                log::error!("Error getting API response: {e}");
-                return vec![];
+    fn name(&self) -> String {
+        String::from("GOG")
+    }
+
+    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Result<ScrapeReport, ScrapeError> {
+        let mut seen_ids = HashSet::new();
+        let fetch_page = |page| async move {
+            if page > 1 {
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
             }
+
+            let url = Url::from_str(format!("{GOG_API_URL}&page={page}").as_str()).unwrap();
+            make_api_request::<model::ApiResponse>(http, &url, HeaderMap::new())
+                .await
+                .map_err(ScrapeError::from)
         };
-        
-        let games = api_response.products;
-        let mut free_games = Vec::with_capacity(games.len());
+        let games = walk_catalog(fetch_page, &mut seen_ids).await?;
+
+        let mut report = ScrapeReport::default();
         
         let offer_until_regex = Regex::new(r#"window.productcardData.cardProductPromoEndDate\s*=\s*\{\"date\":\"(\d{4}-\d{2}-\d{2})"#).unwrap();
         
         for game in games {
+            report.candidate();
+
             tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
             
             if game.price.discount != "-100%" || game.price.r#final != "$0.00" {
+                report.skip(SkipReason::NotFree);
                 continue;
             }
 
@@ -48,8 +63,14 @@ impl Store for GogStore {
                 Ok(false) => {
                     log::debug!("Game {} does not exist.", game.id)
                 },
-                _ => {
+                Ok(true) => {
                     log::debug!("Game {} already exists, skipping.", game.id);
+                    report.skip(SkipReason::AlreadyKnown);
+                    continue;
+                },
+                Err(e) => {
+                    log::error!("Couldn't check if game {} exists: {e}", game.id);
+                    report.skip(SkipReason::RequestFailed);
                     continue;
                 }
             }
@@ -60,11 +81,13 @@ impl Store for GogStore {
             let offer_until = match make_request(http, &store_link, headers).await {
                 Ok(response) => {
                     let Ok(html) = response.text().await else {
+                        report.skip(SkipReason::RequestFailed);
                         continue;
                     };
 
                     let Some(captures) = offer_until_regex.captures(&html) else {
                         log::error!("Couldn\'t find offer_until on page {store_link}");
+                        report.skip(SkipReason::ParseFailed);
                         continue;
                     };
 
@@ -72,22 +95,195 @@ impl Store for GogStore {
                 },
                 Err(e) => {
                     log::error!("Error getting page {}: {e}", store_link.as_str());
+                    report.skip(SkipReason::RequestFailed);
                     continue;
                 }
             };
             
-            free_games.push(Game {
+            report.games.push(Game {
                 id: game.id,
                 store: GameStore::Gog,
                 title: game.title.clone(),
                 identifier: make_identifier(game.title),
                 url: game.store_link,
-                original_price: game.price.base,
+                original_price: Price::parse(&game.price.base),
+                offer_from: None,
                 offer_until,
                 game_type: game.product_type,
+                redeem_on: None,
+                offer_kind: OfferKind::Keep,
+                image_url: game.cover_horizontal,
             })
         }
 
-        free_games
+        // Giveaways are only announced on the homepage and never show up in the catalog
+        match giveaway::get_giveaway(http).await {
+            Ok(Some(game)) => {
+                report.candidate();
+
+                if seen_ids.contains(&game.id) {
+                    log::debug!("Giveaway {} is also discounted in the catalog, skipping.", game.id);
+                    report.skip(SkipReason::AlreadyKnown);
+                    return Ok(report);
+                }
+
+                let partial_game = PartialGame {
+                    id: game.id.clone(),
+                    store: GameStore::Gog,
+                };
+
+                match internal_api.get_game(&partial_game).await {
+                    Ok(false) => report.games.push(game),
+                    Ok(true) => {
+                        log::debug!("Game {} already exists, skipping.", game.id);
+                        report.skip(SkipReason::AlreadyKnown);
+                    },
+                    Err(e) => {
+                        log::error!("Couldn't check if game {} exists: {e}", game.id);
+                        report.skip(SkipReason::RequestFailed);
+                    }
+                }
+            },
+            Ok(None) => log::debug!("No giveaway running on GOG."),
+            Err(reason) => {
+                report.candidate();
+                report.skip(reason);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Collects the products of all catalog pages, `fetch_page` is called with each page number
+/// starting at 1 until the last page (or the last one we are allowed to look at) was read.
+async fn walk_catalog<F, Fut>(mut fetch_page: F, seen_ids: &mut HashSet<String>) -> Result<Vec<model::GogGame>, ScrapeError>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<model::ApiResponse, ScrapeError>>,
+{
+    let mut games = vec![];
+    let mut page = 1;
+
+    loop {
+        let api_response = fetch_page(page).await?;
+
+        // Products can move between pages while we are paging when their ranking changes
+        add_unseen_products(&mut games, seen_ids, api_response.products);
+
+        if page >= api_response.pages.min(GOG_MAX_PAGES) {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(games)
+}
+
+fn add_unseen_products(games: &mut Vec<model::GogGame>, seen_ids: &mut HashSet<String>, products: Vec<model::GogGame>) {
+    for product in products {
+        if seen_ids.insert(product.id.clone()) {
+            games.push(product);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_page(ids: &[&str], pages: usize) -> model::ApiResponse {
+        let products = ids.iter()
+            .map(|id| format!(r#"{{
+                "id": "{id}",
+                "productType": "game",
+                "title": "Game {id}",
+                "price": {{ "final": "$0.00", "base": "$9.99", "discount": "-100%" }},
+                "storeLink": "https://www.gog.com/en/game/game_{id}"
+            }}"#))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        serde_json::from_str(&format!(r#"{{ "pages": {pages}, "products": [{products}] }}"#)).unwrap()
+    }
+
+    #[test]
+    fn test_deduplicate_pages() {
+        let mut games = vec![];
+        let mut seen_ids = HashSet::new();
+
+        let first = parse_page(&["1", "2", "3"], 2);
+        let second = parse_page(&["3", "4"], 2);
+        assert_eq!(first.pages, 2);
+
+        add_unseen_products(&mut games, &mut seen_ids, first.products);
+        add_unseen_products(&mut games, &mut seen_ids, second.products);
+
+        let ids = games.iter().map(|game| game.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["1", "2", "3", "4"]);
+    }
+
+    fn game_ids(games: &[model::GogGame]) -> Vec<&str> {
+        games.iter().map(|game| game.id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_walk_all_pages() {
+        let mut pages = vec![];
+        let fetch_page = |page| {
+            pages.push(page);
+            let ids: &[&str] = match page {
+                1 => &["1", "2", "3"],
+                2 => &["3", "4"],
+                3 => &["5"],
+                _ => panic!("requested a page that doesn't exist"),
+            };
+            std::future::ready(Ok(parse_page(ids, 3)))
+        };
+        let mut seen_ids = HashSet::new();
+        let games = walk_catalog(fetch_page, &mut seen_ids).await.unwrap();
+
+        assert_eq!(pages, vec![1, 2, 3]);
+        assert_eq!(game_ids(&games), vec!["1", "2", "3", "4", "5"]);
+        assert!(seen_ids.contains("5"));
+    }
+
+    #[tokio::test]
+    async fn test_walk_stops_at_max_pages() {
+        let mut pages = 0;
+        let fetch_page = |page: usize| {
+            pages += 1;
+            std::future::ready(Ok(parse_page(&[&page.to_string()], 100)))
+        };
+        let games = walk_catalog(fetch_page, &mut HashSet::new()).await.unwrap();
+
+        assert_eq!(pages, GOG_MAX_PAGES);
+        assert_eq!(games.len(), GOG_MAX_PAGES);
+    }
+
+    #[tokio::test]
+    async fn test_walk_empty_catalog() {
+        let mut pages = 0;
+        let fetch_page = |_| {
+            pages += 1;
+            std::future::ready(Ok(parse_page(&[], 0)))
+        };
+        let games = walk_catalog(fetch_page, &mut HashSet::new()).await.unwrap();
+
+        assert_eq!(pages, 1);
+        assert!(games.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_walk_fails_with_page() {
+        let fetch_page = |page| std::future::ready(match page {
+            1 => Ok(parse_page(&["1"], 2)),
+            _ => Err(ScrapeError::Request("503 Service Unavailable".to_string())),
+        });
+
+        assert!(matches!(
+            walk_catalog(fetch_page, &mut HashSet::new()).await,
+            Err(ScrapeError::Request(_))
+        ));
     }
 }
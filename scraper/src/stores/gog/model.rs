@@ -3,6 +3,8 @@ use utils::model::GameType;
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct ApiResponse {
+    #[serde(alias = "totalPages")]
+    pub pages: usize,
     pub products: Vec<GogGame>,
 }
 
@@ -15,6 +17,7 @@ pub(crate) struct GogGame {
     pub title: String,
     pub price: Price,
     pub store_link: String,
+    pub cover_horizontal: Option<String>,
 }
 
 fn deserialize_game_type<'de, D>(deserializer: D) -> Result<GameType, D::Error>
@@ -0,0 +1,248 @@
+mod model;
+
+use std::str::FromStr;
+use chrono::DateTime;
+use reqwest::{Client, Url};
+use reqwest::header::HeaderMap;
+use utils::internal_api::InternalApi;
+use utils::model::{Game, GameStore, OfferKind, PartialGame, Price};
+use crate::stores::{make_api_request, make_identifier, ScrapeError, ScrapeReport, SkipReason, Store};
+
+pub struct HumbleStore;
+
+const HUMBLE_BASE_STORE_URL: &str = "https://www.humblebundle.com/store";
+const HUMBLE_API_URL: &str =
+    "https://www.humblebundle.com/store/api/search?sort=discount&filter=onsale&request=1";
+// Upper bound so a misbehaving API can't keep us paging forever
+const HUMBLE_MAX_PAGES: usize = 10;
+
+#[async_trait::async_trait]
+impl Store for HumbleStore {
+    fn name(&self) -> String {
+        String::from("Humble")
+    }
+
+    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Result<ScrapeReport, ScrapeError> {
+        let fetch_page = |page| async move {
+            if page > 0 {
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            }
+
+            let url = Url::from_str(format!("{HUMBLE_API_URL}&page={page}").as_str()).unwrap();
+            make_api_request::<model::ApiResponse>(http, &url, HeaderMap::new())
+                .await
+                .map_err(ScrapeError::from)
+        };
+
+        let products = walk_search_results(fetch_page).await?;
+        let mut report = ScrapeReport::expecting_candidates();
+
+        for product in products {
+            report.candidate();
+
+            let game = match product_to_game(product) {
+                Ok(game) => game,
+                Err(reason) => {
+                    report.skip(reason);
+                    continue;
+                }
+            };
+
+            let partial_game = PartialGame {
+                id: game.id.clone(),
+                store: GameStore::Humble,
+            };
+
+            match internal_api.get_game(&partial_game).await {
+                Ok(false) => {
+                    log::debug!("Game {} does not exist.", game.id)
+                },
+                Ok(true) => {
+                    log::debug!("Game {} already exists, skipping.", game.id);
+                    report.skip(SkipReason::AlreadyKnown);
+                    continue;
+                },
+                Err(e) => {
+                    log::error!("Couldn't check if game {} exists: {e}", game.id);
+                    report.skip(SkipReason::RequestFailed);
+                    continue;
+                }
+            }
+
+            report.games.push(game);
+        }
+
+        Ok(report)
+    }
+}
+
+/// Collects the products of all search pages that can still have free games. The search is sorted
+/// by discount, so `fetch_page` is called with each page index starting at 0 until a page has a
+/// product that isn't discounted to free or the results run out.
+async fn walk_search_results<F, Fut>(mut fetch_page: F) -> Result<Vec<model::HumbleProduct>, ScrapeError>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<model::ApiResponse, ScrapeError>>,
+{
+    let mut products = vec![];
+    let mut page = 0;
+
+    loop {
+        let api_response = fetch_page(page).await?;
+        let all_free = api_response.results.iter().all(is_discounted_to_free);
+        let is_empty = api_response.results.is_empty();
+        products.extend(api_response.results);
+
+        page += 1;
+        if !all_free || is_empty || page >= api_response.num_pages.min(HUMBLE_MAX_PAGES) {
+            break;
+        }
+    }
+
+    Ok(products)
+}
+
+fn is_discounted_to_free(product: &model::HumbleProduct) -> bool {
+    product.current_price.amount == 0.0 && product.full_price.amount > 0.0
+}
+
+fn product_to_game(product: model::HumbleProduct) -> Result<Game, SkipReason> {
+    // Products that are free without a discount aren't giveaways either
+    if !is_discounted_to_free(&product) {
+        return Err(SkipReason::NotFree);
+    }
+
+    // Humble only sends the sale end as a unix timestamp
+    let offer_until = product.sale_end
+        .and_then(|sale_end| DateTime::from_timestamp(sale_end, 0))
+        .ok_or(SkipReason::ParseFailed)?
+        .date_naive();
+
+    let original_price = Price::from_major(product.full_price.amount, &product.full_price.currency);
+
+    Ok(Game {
+        id: product.machine_name,
+        store: GameStore::Humble,
+        identifier: make_identifier(product.human_name.clone()),
+        title: product.human_name,
+        url: format!("{HUMBLE_BASE_STORE_URL}/{}", product.human_url),
+        original_price: Some(original_price),
+        offer_from: None,
+        offer_until,
+        game_type: product.game_type,
+        redeem_on: None,
+        offer_kind: OfferKind::Keep,
+        image_url: product.large_capsule,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use super::*;
+
+    fn parse_product(current_price: f64, sale_end: &str) -> model::HumbleProduct {
+        serde_json::from_str(&format!(r#"{{
+            "machine_name": "thegame_storefront",
+            "human_name": "The Game: Deluxe",
+            "human_url": "the-game-deluxe",
+            "content_types": ["game"],
+            "full_price": {{ "amount": 19.99, "currency": "USD" }},
+            "current_price": {{ "amount": {current_price}, "currency": "USD" }},
+            "sale_end": {sale_end}
+        }}"#)).unwrap()
+    }
+
+    #[test]
+    fn test_free_product() {
+        let game = product_to_game(parse_product(0.0, "1750406400")).unwrap();
+
+        assert_eq!(game.id, "thegame_storefront");
+        assert_eq!(game.identifier, "The_Game_Deluxe");
+        assert_eq!(game.url, "https://www.humblebundle.com/store/the-game-deluxe");
+        assert_eq!(game.original_price, Some(Price::new(1999, "USD")));
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 20).unwrap());
+    }
+
+    #[test]
+    fn test_discounted_product() {
+        assert_eq!(product_to_game(parse_product(4.99, "1750406400")).unwrap_err(), SkipReason::NotFree);
+    }
+
+    fn parse_page(prices: &[f64], num_pages: usize) -> model::ApiResponse {
+        let results = prices.iter()
+            .enumerate()
+            .map(|(i, price)| format!(r#"{{
+                "machine_name": "game_{i}_storefront",
+                "human_name": "Game {i}",
+                "human_url": "game-{i}",
+                "content_types": ["game"],
+                "full_price": {{ "amount": 19.99, "currency": "USD" }},
+                "current_price": {{ "amount": {price}, "currency": "USD" }},
+                "sale_end": 1750406400
+            }}"#))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        serde_json::from_str(&format!(r#"{{ "num_pages": {num_pages}, "results": [{results}] }}"#)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_walk_until_not_free() {
+        let mut pages = vec![];
+        let fetch_page = |page| {
+            pages.push(page);
+            std::future::ready(Ok(match page {
+                0 => parse_page(&[0.0, 0.0], 50),
+                1 => parse_page(&[0.0, 4.99], 50),
+                _ => panic!("requested a page without free games"),
+            }))
+        };
+        let products = walk_search_results(fetch_page).await.unwrap();
+
+        assert_eq!(pages, vec![0, 1]);
+        assert_eq!(products.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_walk_until_results_run_out() {
+        let mut pages = vec![];
+        let fetch_page = |page| {
+            pages.push(page);
+            std::future::ready(Ok(match page {
+                0 => parse_page(&[0.0, 0.0], 2),
+                1 => parse_page(&[0.0], 2),
+                _ => panic!("requested a page that doesn't exist"),
+            }))
+        };
+        let products = walk_search_results(fetch_page).await.unwrap();
+
+        assert_eq!(pages, vec![0, 1]);
+        assert_eq!(products.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_walk_stops_at_max_pages() {
+        let mut pages = 0;
+        let fetch_page = |_| {
+            pages += 1;
+            std::future::ready(Ok(parse_page(&[0.0], 100)))
+        };
+        let products = walk_search_results(fetch_page).await.unwrap();
+
+        assert_eq!(pages, HUMBLE_MAX_PAGES);
+        assert_eq!(products.len(), HUMBLE_MAX_PAGES);
+    }
+
+    #[tokio::test]
+    async fn test_walk_empty_results() {
+        let fetch_page = |_| std::future::ready(Ok(parse_page(&[], 100)));
+
+        assert!(walk_search_results(fetch_page).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_product_without_sale_end() {
+        assert_eq!(product_to_game(parse_product(0.0, "null")).unwrap_err(), SkipReason::ParseFailed);
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Deserializer};
+use utils::model::GameType;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApiResponse {
+    #[serde(default)]
+    pub num_pages: usize,
+    pub results: Vec<HumbleProduct>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct HumbleProduct {
+    pub machine_name: String,
+    pub human_name: String,
+    pub human_url: String,
+    #[serde(rename = "content_types", deserialize_with = "deserialize_game_type")]
+    pub game_type: GameType,
+    pub full_price: Price,
+    pub current_price: Price,
+    pub sale_end: Option<i64>,
+    pub large_capsule: Option<String>,
+}
+
+fn deserialize_game_type<'de, D>(deserializer: D) -> Result<GameType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let content_types = Vec::<String>::deserialize(deserializer)?;
+    match content_types.first().map(String::as_str) {
+        Some("game") => Ok(GameType::Game),
+        Some("dlc") => Ok(GameType::Dlc),
+        Some("software") => Ok(GameType::Software),
+        Some("bundle") => Ok(GameType::Bundle),
+        _ => Ok(GameType::Unknown),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Price {
+    pub amount: f64,
+    pub currency: String,
+}
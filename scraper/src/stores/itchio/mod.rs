@@ -0,0 +1,355 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+use chrono::NaiveDate;
+use regex::Regex;
+use reqwest::{Client, Url};
+use reqwest::header::HeaderMap;
+use scraper::{Html, Selector};
+use utils::internal_api::InternalApi;
+use utils::model::{Game, GameStore, GameType, OfferKind, PartialGame, Price};
+use crate::stores::{make_identifier, make_request, ScrapeError, ScrapeReport, SkipReason, Store};
+
+pub struct ItchIoStore;
+
+const ITCH_IO_SALES_URL: &str = "https://itch.io/games/on-sale";
+// The listing has every discount and isn't sorted by it, so free games can be on any page. Upper
+// bound so we don't page through all sales of itch.io every time.
+const ITCH_IO_MAX_PAGES: usize = 20;
+
+struct SaleListing {
+    id: String,
+    title: String,
+    url: String,
+    image_url: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl Store for ItchIoStore {
+    fn name(&self) -> String {
+        String::from("ItchIo")
+    }
+
+    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Result<ScrapeReport, ScrapeError> {
+        let fetch_page = |page| async move {
+            if page > 1 {
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            }
+
+            let sales_url = Url::from_str(format!("{ITCH_IO_SALES_URL}?page={page}").as_str()).unwrap();
+            Ok::<_, ScrapeError>(make_request(http, &sales_url, HeaderMap::new()).await?.text().await?)
+        };
+
+        let mut report = ScrapeReport::expecting_candidates();
+        let listings = walk_sale_listings(fetch_page, &mut report).await?;
+
+        for listing in listings {
+            let partial_game = PartialGame {
+                id: listing.id.clone(),
+                store: GameStore::ItchIo,
+            };
+
+            match internal_api.get_game(&partial_game).await {
+                Ok(false) => {
+                    log::debug!("Game {} does not exist.", listing.id)
+                },
+                Ok(true) => {
+                    log::debug!("Game {} already exists, skipping.", listing.id);
+                    report.skip(SkipReason::AlreadyKnown);
+                    continue;
+                },
+                Err(e) => {
+                    log::error!("Couldn't check if game {} exists: {e}", listing.id);
+                    report.skip(SkipReason::RequestFailed);
+                    continue;
+                }
+            }
+
+            let Ok(game_url) = Url::from_str(&listing.url) else {
+                report.skip(SkipReason::ParseFailed);
+                continue;
+            };
+
+            let game_page = match make_request(http, &game_url, HeaderMap::new()).await {
+                Ok(response) => match response.text().await {
+                    Ok(html) => html,
+                    Err(_) => {
+                        report.skip(SkipReason::RequestFailed);
+                        continue;
+                    },
+                },
+                Err(e) => {
+                    log::error!("Error getting page {}: {e}", game_url.as_str());
+                    report.skip(SkipReason::RequestFailed);
+                    continue;
+                }
+            };
+
+            match parse_game_page(&game_page, listing) {
+                Some(game) => report.games.push(game),
+                None => {
+                    log::error!("Couldn't parse game page {}", game_url.as_str());
+                    report.skip(SkipReason::ParseFailed);
+                },
+            }
+
+            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        }
+
+        Ok(report)
+    }
+}
+
+/// Collects the free games of all sale pages, `fetch_page` is called with each page number starting
+/// at 1 until a page has no games (or the last one we are allowed to look at) was read.
+async fn walk_sale_listings<F, Fut>(mut fetch_page: F, report: &mut ScrapeReport) -> Result<Vec<SaleListing>, ScrapeError>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<String, ScrapeError>>,
+{
+    let mut listings = vec![];
+    let mut seen_ids = HashSet::new();
+    let mut page = 1;
+
+    loop {
+        let html = fetch_page(page).await?;
+        let candidates = report.candidates;
+
+        match parse_sale_listings(&html, report) {
+            Ok(page_listings) => for listing in page_listings {
+                // Games can move between pages while we are paging when their ranking changes
+                if seen_ids.insert(listing.id.clone()) {
+                    listings.push(listing);
+                } else {
+                    report.skip(SkipReason::AlreadyKnown);
+                }
+            },
+            // Pages past the end of the listing have no game grid
+            Err(ScrapeError::Layout(_)) if page > 1 => break,
+            Err(e) => return Err(e),
+        }
+
+        if report.candidates == candidates || page >= ITCH_IO_MAX_PAGES {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(listings)
+}
+
+fn parse_sale_listings(html: &str, report: &mut ScrapeReport) -> Result<Vec<SaleListing>, ScrapeError> {
+    let game_grid_selector = Selector::parse(r#"div.browse_game_grid"#).unwrap();
+    let game_cell_selector = Selector::parse(r#"div.game_cell"#).unwrap();
+    let title_selector = Selector::parse(r#"a.title.game_link"#).unwrap();
+    let sale_tag_selector = Selector::parse(r#"div.sale_tag"#).unwrap();
+    let thumbnail_selector = Selector::parse(r#"div.game_thumb img"#).unwrap();
+
+    let document = Html::parse_document(html);
+    let mut listings = vec![];
+
+    let Some(game_grid) = document.select(&game_grid_selector).next() else {
+        return Err(ScrapeError::Layout("game grid not found".to_string()));
+    };
+
+    for element in game_grid.select(&game_cell_selector) {
+        report.candidate();
+
+        // Everything listed here is on sale, so a game without a sale tag means we can't read it
+        let Some(sale_tag) = element.select(&sale_tag_selector).next() else {
+            report.skip(SkipReason::ParseFailed);
+            continue;
+        };
+
+        if sale_tag.inner_html().trim() != "-100%" {
+            report.skip(SkipReason::NotFree);
+            continue;
+        }
+
+        let (Some(id), Some(title_link)) = (element.attr("data-game_id"), element.select(&title_selector).next()) else {
+            report.skip(SkipReason::ParseFailed);
+            continue;
+        };
+        let Some(url) = title_link.attr("href") else {
+            report.skip(SkipReason::ParseFailed);
+            continue;
+        };
+
+        // Thumbnails are lazy loaded, so the actual source is only set once they are visible
+        let image_url = element.select(&thumbnail_selector)
+            .next()
+            .and_then(|image| image.attr("data-lazy_src").or(image.attr("src")))
+            .map(str::to_string);
+
+        listings.push(SaleListing {
+            id: id.to_string(),
+            title: title_link.text().collect::<String>().trim().to_string(),
+            url: url.to_string(),
+            image_url,
+        });
+    }
+
+    Ok(listings)
+}
+
+fn parse_game_page(html: &str, listing: SaleListing) -> Option<Game> {
+    let original_price_selector = Selector::parse(r#"span.original_price"#).ok()?;
+    // The sale details are part of the page's init script, the visible countdown is rendered by JS
+    let sale_end_regex = Regex::new(r#""sale":\{[^}]*"end_date":"(\d{4}-\d{2}-\d{2})"#).unwrap();
+
+    let document = Html::parse_document(html);
+
+    let original_price = Price::parse(&document.select(&original_price_selector).next()?.inner_html());
+    let offer_until = {
+        let captures = sale_end_regex.captures(html)?;
+        NaiveDate::parse_from_str(&captures[1], "%Y-%m-%d").ok()?
+    };
+
+    Some(Game {
+        id: listing.id,
+        store: GameStore::ItchIo,
+        identifier: make_identifier(listing.title.clone()),
+        title: listing.title,
+        url: listing.url,
+        original_price,
+        offer_from: None,
+        offer_until,
+        game_type: GameType::Game,
+        redeem_on: None,
+        offer_kind: OfferKind::Keep,
+        image_url: listing.image_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ON_SALE_FIXTURE: &str = include_str!("../../../fixtures/itchio/on_sale.html");
+    const GAME_PAGE_FIXTURE: &str = include_str!("../../../fixtures/itchio/game_page.html");
+
+    fn parse_fixture_listings() -> Vec<SaleListing> {
+        parse_sale_listings(ON_SALE_FIXTURE, &mut ScrapeReport::default()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_sale_listings() {
+        let mut report = ScrapeReport::default();
+        let listings = parse_sale_listings(ON_SALE_FIXTURE, &mut report).unwrap();
+
+        assert_eq!(report.candidates, 3);
+        assert_eq!(report.skipped.get(&SkipReason::NotFree), Some(&1));
+        assert_eq!(listings.len(), 2);
+        assert_eq!(listings[0].id, "1234567");
+        assert_eq!(listings[0].title, "Forest Tales: Chapter 1");
+        assert_eq!(listings[0].url, "https://somedev.itch.io/forest-tales");
+        assert_eq!(listings[0].image_url.as_deref(), Some("https://img.itch.zone/aW1nLzEyMzQ1Njc=/315x250%23c/forest.png"));
+        assert_eq!(listings[1].id, "2468024");
+    }
+
+    /// The fixture with its game ids moved, so every page lists other games.
+    fn make_page(page: usize) -> String {
+        ON_SALE_FIXTURE.replace(r#"data-game_id=""#, &format!(r#"data-game_id="{page}"#))
+    }
+
+    #[tokio::test]
+    async fn test_walk_all_pages() {
+        let mut pages = vec![];
+        let fetch_page = |page| {
+            pages.push(page);
+            std::future::ready(Ok(match page {
+                1 => make_page(1),
+                // The same games again, e.g. because their ranking changed
+                2 => make_page(1),
+                3 => make_page(3),
+                _ => ON_SALE_FIXTURE.replace("data-game_id", "data-other_id").replace("game_cell", "game_row"),
+            }))
+        };
+        let mut report = ScrapeReport::default();
+        let listings = walk_sale_listings(fetch_page, &mut report).await.unwrap();
+
+        assert_eq!(pages, vec![1, 2, 3, 4]);
+        assert_eq!(report.candidates, 9);
+        assert_eq!(report.skipped.get(&SkipReason::AlreadyKnown), Some(&2));
+        let ids = listings.iter().map(|listing| listing.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["11234567", "12468024", "31234567", "32468024"]);
+    }
+
+    #[tokio::test]
+    async fn test_walk_stops_without_grid() {
+        let mut pages = vec![];
+        let fetch_page = |page| {
+            pages.push(page);
+            std::future::ready(Ok(match page {
+                1 => make_page(1),
+                _ => "<html><body><p>No results</p></body></html>".to_string(),
+            }))
+        };
+        let listings = walk_sale_listings(fetch_page, &mut ScrapeReport::default()).await.unwrap();
+
+        assert_eq!(pages, vec![1, 2]);
+        assert_eq!(listings.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_walk_stops_at_max_pages() {
+        let mut pages = 0;
+        let fetch_page = |page| {
+            pages += 1;
+            std::future::ready(Ok(make_page(page)))
+        };
+        let listings = walk_sale_listings(fetch_page, &mut ScrapeReport::default()).await.unwrap();
+
+        assert_eq!(pages, ITCH_IO_MAX_PAGES);
+        assert_eq!(listings.len(), 2 * ITCH_IO_MAX_PAGES);
+    }
+
+    #[tokio::test]
+    async fn test_walk_changed_layout() {
+        let fetch_page = |_| std::future::ready(Ok(ON_SALE_FIXTURE.replace("browse_game_grid", "game_list")));
+
+        assert!(matches!(
+            walk_sale_listings(fetch_page, &mut ScrapeReport::default()).await,
+            Err(ScrapeError::Layout(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_sale_listing_without_tag() {
+        let html = ON_SALE_FIXTURE.replace(r#"<div class="sale_tag">-50%</div>"#, "");
+        let mut report = ScrapeReport::default();
+        let listings = parse_sale_listings(&html, &mut report).unwrap();
+
+        assert_eq!(listings.len(), 2);
+        assert_eq!(report.skipped.get(&SkipReason::NotFree), None);
+        assert_eq!(report.skipped.get(&SkipReason::ParseFailed), Some(&1));
+    }
+
+    #[test]
+    fn test_parse_game_page() {
+        let listing = parse_fixture_listings().remove(0);
+        let game = parse_game_page(GAME_PAGE_FIXTURE, listing).unwrap();
+
+        assert_eq!(game.id, "1234567");
+        assert_eq!(game.identifier, "Forest_Tales_Chapter_1");
+        assert_eq!(game.original_price, Some(Price::new(499, "USD")));
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 20).unwrap());
+    }
+
+    #[test]
+    fn test_parse_game_page_without_sale() {
+        let listing = parse_fixture_listings().remove(0);
+        let html = GAME_PAGE_FIXTURE.replace(r#""sale":{"#, r#""bundle":{"#);
+
+        assert!(parse_game_page(&html, listing).is_none());
+    }
+
+    #[test]
+    fn test_parse_changed_layout() {
+        let html = ON_SALE_FIXTURE.replace("browse_game_grid", "game_list");
+
+        assert!(matches!(
+            parse_sale_listings(&html, &mut ScrapeReport::default()),
+            Err(ScrapeError::Layout(_))
+        ));
+    }
+}
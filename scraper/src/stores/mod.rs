@@ -1,21 +1,136 @@
+use std::collections::BTreeMap;
+use std::fmt;
 use regex::Regex;
 use reqwest::{Client, Url};
 use reqwest::header::HeaderMap;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use utils::model::Game;
 
 mod steam;
 mod epicgames;
 mod gog;
+mod itchio;
+mod humble;
+mod primegaming;
 
 pub use epicgames::EpicGamesStore;
 pub use gog::GogStore;
+pub use humble::HumbleStore;
+pub use itchio::ItchIoStore;
+pub use primegaming::PrimeGamingStore;
 pub use steam::SteamStore;
 use utils::internal_api::InternalApi;
 
 #[async_trait::async_trait]
 pub(crate) trait Store {
-    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Vec<Game>;
+    fn name(&self) -> String;
+
+    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Result<ScrapeReport, ScrapeError>;
+}
+
+/// Outcome of a single scrape of a store. Every candidate the store listed is either parsed into
+/// a game or skipped with a reason.
+#[derive(Debug, Default)]
+pub(crate) struct ScrapeReport {
+    pub games: Vec<Game>,
+    pub candidates: usize,
+    pub skipped: BTreeMap<SkipReason, usize>,
+    /// The store's listing is never empty, e.g. because it lists every sale and not just free games.
+    pub expects_candidates: bool,
+}
+
+impl ScrapeReport {
+    fn expecting_candidates() -> Self {
+        Self {
+            expects_candidates: true,
+            ..Self::default()
+        }
+    }
+
+    fn candidate(&mut self) {
+        self.candidates += 1;
+    }
+
+    fn skip(&mut self, reason: SkipReason) {
+        *self.skipped.entry(reason).or_default() += 1;
+    }
+
+    fn skipped_for(&self, reason: SkipReason) -> usize {
+        self.skipped.get(&reason).copied().unwrap_or_default()
+    }
+
+    /// No games were parsed and either some candidates failed or a listing that is never empty had
+    /// none. Usually this means the store changed its layout, a store without free games isn't degraded.
+    pub fn is_degraded(&self) -> bool {
+        let failed = self.skipped_for(SkipReason::ParseFailed) + self.skipped_for(SkipReason::RequestFailed);
+        let missing = self.expects_candidates && self.candidates == 0;
+        self.games.is_empty() && (failed > 0 || missing)
+    }
+}
+
+impl fmt::Display for ScrapeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} candidates, {} parsed", self.candidates, self.games.len())?;
+        for (reason, count) in &self.skipped {
+            write!(f, ", {count} {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SkipReason {
+    NotFree,
+    AlreadyKnown,
+    RequestFailed,
+    ParseFailed,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::NotFree => write!(f, "not free"),
+            SkipReason::AlreadyKnown => write!(f, "already known"),
+            SkipReason::RequestFailed => write!(f, "request failed"),
+            SkipReason::ParseFailed => write!(f, "parse failed"),
+        }
+    }
+}
+
+/// Errors that prevent a store from being scraped at all.
+#[derive(Debug)]
+pub(crate) enum ScrapeError {
+    /// The store could not be reached or answered with garbage.
+    Request(String),
+    /// The store answered, but not in the shape we expect.
+    Layout(String),
+}
+
+impl fmt::Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScrapeError::Request(e) => write!(f, "request failed: {e}"),
+            ScrapeError::Layout(e) => write!(f, "layout changed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ScrapeError {}
+
+impl From<reqwest::Error> for ScrapeError {
+    fn from(e: reqwest::Error) -> Self {
+        ScrapeError::Request(e.to_string())
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for ScrapeError {
+    fn from(e: Box<dyn std::error::Error>) -> Self {
+        match e.downcast::<serde_json::Error>() {
+            Ok(e) => ScrapeError::Layout(e.to_string()),
+            Err(e) => ScrapeError::Request(e.to_string()),
+        }
+    }
 }
 
 async fn make_request(http: &Client, url: &Url, mut headers: HeaderMap) -> Result<reqwest::Response, reqwest::Error> {
@@ -23,9 +138,10 @@ async fn make_request(http: &Client, url: &Url, mut headers: HeaderMap) -> Resul
 
     let request = http.get(url.clone()).headers(headers);
 
+    // Blocked or failed requests often still come with a page, which must not be parsed as the listing
     match request.send().await {
         Ok(response) => {
-            Ok(response)
+            response.error_for_status()
         },
         Err(e) => Err(e)
     }
@@ -36,7 +152,17 @@ where D: DeserializeOwned
 {
     headers.append("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:133.0) Gecko/20100101 Firefox/133.0".parse()?);
 
-    let request = http.get(url.clone()).headers(headers).send().await?;
+    let request = http.get(url.clone()).headers(headers).send().await?.error_for_status()?;
+    let text = request.text().await?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+async fn make_api_post_request<D, B>(http: &Client, url: &Url, mut headers: HeaderMap, body: &B) -> Result<D, Box<dyn std::error::Error>>
+where D: DeserializeOwned, B: Serialize
+{
+    headers.append("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:133.0) Gecko/20100101 Firefox/133.0".parse()?);
+
+    let request = http.post(url.clone()).headers(headers).json(body).send().await?.error_for_status()?;
     let text = request.text().await?;
     Ok(serde_json::from_str(&text)?)
 }
@@ -53,8 +179,64 @@ fn make_identifier(mut name: String) -> String {
 
 #[cfg(test)]
 mod tests {
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::method;
     use super::*;
 
+    #[test]
+    fn test_report_degraded() {
+        let mut report = ScrapeReport::default();
+        report.candidate();
+        report.skip(SkipReason::AlreadyKnown);
+        assert!(!report.is_degraded());
+
+        report.candidate();
+        report.skip(SkipReason::ParseFailed);
+        assert!(report.is_degraded());
+        assert_eq!(report.to_string(), "2 candidates, 0 parsed, 1 already known, 1 parse failed");
+    }
+
+    #[test]
+    fn test_report_not_degraded_without_free_games() {
+        let mut report = ScrapeReport::default();
+        assert!(!report.is_degraded());
+
+        report.candidate();
+        report.skip(SkipReason::NotFree);
+        assert!(!report.is_degraded());
+    }
+
+    #[test]
+    fn test_report_degraded_without_candidates() {
+        let mut report = ScrapeReport::expecting_candidates();
+        assert!(report.is_degraded());
+
+        report.candidate();
+        report.skip(SkipReason::NotFree);
+        assert!(!report.is_degraded());
+    }
+
+    #[tokio::test]
+    async fn test_error_status_is_request_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("<html>Service Unavailable</html>"))
+            .mount(&server)
+            .await;
+        let url = Url::parse(&server.uri()).unwrap();
+        let http = Client::new();
+
+        assert!(make_request(&http, &url, HeaderMap::new()).await.is_err());
+        let result = make_api_request::<serde_json::Value>(&http, &url, HeaderMap::new()).await;
+        assert!(matches!(ScrapeError::from(result.unwrap_err()), ScrapeError::Request(_)));
+    }
+
+    #[test]
+    fn test_json_error_is_layout() {
+        let error: Box<dyn std::error::Error> = serde_json::from_str::<u8>("{}").unwrap_err().into();
+        assert!(matches!(ScrapeError::from(error), ScrapeError::Layout(_)));
+    }
+
     #[test]
     fn test_basic() {
         assert_eq!(
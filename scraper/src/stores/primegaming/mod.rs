@@ -0,0 +1,162 @@
+mod model;
+
+use std::str::FromStr;
+use chrono::NaiveDate;
+use reqwest::{Client, Url};
+use reqwest::header::HeaderMap;
+use utils::internal_api::InternalApi;
+use utils::model::{Game, GameStore, GameType, Launcher, OfferKind, PartialGame};
+use crate::stores::{make_api_post_request, make_identifier, ScrapeError, ScrapeReport, SkipReason, Store};
+
+pub struct PrimeGamingStore;
+
+const PG_BASE_STORE_URL: &str = "https://gaming.amazon.com/home";
+const PG_API_URL: &str = "https://gaming.amazon.com/graphql";
+const PG_OFFERS_QUERY: &str = r#"query OffersContext_Offers {
+    primeOffers(dateOverride: null, group: "FGWP_FULL") {
+        id
+        title
+        deliveryMethod
+        startTime
+        endTime
+        content { externalURL publisher }
+        claimInstructions
+    }
+}"#;
+
+#[async_trait::async_trait]
+impl Store for PrimeGamingStore {
+    fn name(&self) -> String {
+        String::from("PrimeGaming")
+    }
+
+    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Result<ScrapeReport, ScrapeError> {
+        let url = Url::from_str(PG_API_URL).unwrap();
+        let body = model::GraphQlRequest {
+            operation_name: "OffersContext_Offers",
+            query: PG_OFFERS_QUERY,
+        };
+
+        let api_response = make_api_post_request::<model::ApiResponse, _>(http, &url, HeaderMap::new(), &body).await?;
+
+        let offers = api_response.data.prime_offers;
+        let mut report = ScrapeReport::expecting_candidates();
+
+        for offer in offers {
+            report.candidate();
+
+            let partial_game = PartialGame {
+                id: offer.id.clone(),
+                store: GameStore::PrimeGaming,
+            };
+
+            match internal_api.get_game(&partial_game).await {
+                Ok(false) => {
+                    log::debug!("Game {} does not exist.", offer.id)
+                },
+                Ok(true) => {
+                    log::debug!("Game {} already exists, skipping.", offer.id);
+                    report.skip(SkipReason::AlreadyKnown);
+                    continue;
+                },
+                Err(e) => {
+                    log::error!("Couldn't check if game {} exists: {e}", offer.id);
+                    report.skip(SkipReason::RequestFailed);
+                    continue;
+                }
+            }
+
+            match offer_to_game(offer) {
+                Some(game) => report.games.push(game),
+                None => report.skip(SkipReason::ParseFailed),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+fn offer_to_game(offer: model::PrimeOffer) -> Option<Game> {
+    let offer_until = NaiveDate::parse_from_str(offer.end_time.get(0..10)?, "%Y-%m-%d").ok()?;
+    let redeem_on = get_launcher(&offer);
+
+    let url = offer.content
+        .and_then(|content| content.external_url)
+        .unwrap_or(PG_BASE_STORE_URL.to_string());
+
+    Some(Game {
+        id: offer.id,
+        store: GameStore::PrimeGaming,
+        identifier: make_identifier(offer.title.clone()),
+        title: offer.title,
+        url,
+        // Prime Gaming doesn't publish a price for its offers
+        original_price: None,
+        offer_from: None,
+        offer_until,
+        game_type: GameType::Game,
+        redeem_on: Some(redeem_on),
+        offer_kind: OfferKind::Keep,
+        image_url: None,
+    })
+}
+
+fn get_launcher(offer: &model::PrimeOffer) -> Launcher {
+    // Games that are directly entitled are claimed and played through the Amazon Games app,
+    // everything else hands out a key whose launcher is only mentioned in the claim instructions.
+    if offer.delivery_method.as_str() == "DIRECT_ENTITLEMENT" {
+        return Launcher::AmazonGames;
+    }
+
+    let Some(ref instructions) = offer.claim_instructions else {
+        return Launcher::Unknown;
+    };
+    let instructions = instructions.to_lowercase();
+
+    if instructions.contains("epic games") {
+        Launcher::EpicGames
+    } else if instructions.contains("gog") {
+        Launcher::Gog
+    } else if instructions.contains("legacy games") {
+        Launcher::LegacyGames
+    } else {
+        Launcher::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_offer(delivery_method: &str, claim_instructions: &str) -> model::PrimeOffer {
+        serde_json::from_str(&format!(r#"{{
+            "id": "amzn1.pg.item.1234",
+            "title": "The Game",
+            "deliveryMethod": "{delivery_method}",
+            "startTime": "2025-06-01T16:00:00Z",
+            "endTime": "2025-06-30T16:00:00Z",
+            "content": {{ "externalURL": "https://gaming.amazon.com/the-game/dp/amzn1.pg.item.1234", "publisher": "Someone" }},
+            "claimInstructions": "{claim_instructions}"
+        }}"#)).unwrap()
+    }
+
+    #[test]
+    fn test_amazon_games_offer() {
+        let game = offer_to_game(parse_offer("DIRECT_ENTITLEMENT", "")).unwrap();
+
+        assert_eq!(game.redeem_on, Some(Launcher::AmazonGames));
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 30).unwrap());
+        assert_eq!(game.url, "https://gaming.amazon.com/the-game/dp/amzn1.pg.item.1234");
+    }
+
+    #[test]
+    fn test_key_offers() {
+        let epic = parse_offer("EXTERNAL_OFFER", "Redeem your code on the Epic Games Store.");
+        let gog = parse_offer("EXTERNAL_OFFER", "Redeem your code at GOG.com/redeem.");
+        let legacy = parse_offer("EXTERNAL_OFFER", "Redeem your code in the Legacy Games launcher.");
+
+        assert_eq!(get_launcher(&epic), Launcher::EpicGames);
+        assert_eq!(get_launcher(&gog), Launcher::Gog);
+        assert_eq!(get_launcher(&legacy), Launcher::LegacyGames);
+    }
+}
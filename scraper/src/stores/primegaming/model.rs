@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GraphQlRequest {
+    pub operation_name: &'static str,
+    pub query: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApiResponse {
+    pub data: Data,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Data {
+    pub prime_offers: Vec<PrimeOffer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PrimeOffer {
+    pub id: String,
+    pub title: String,
+    pub delivery_method: String,
+    // pub start_time: String,
+    pub end_time: String,
+    pub content: Option<Content>,
+    pub claim_instructions: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Content {
+    #[serde(rename = "externalURL")]
+    pub external_url: Option<String>,
+    // pub publisher: Option<String>,
+}
@@ -1,79 +1,106 @@
+mod model;
+
 use std::str::FromStr;
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, Days, NaiveDate, Utc};
 use regex::Regex;
 use reqwest::{Client, Url};
 use reqwest::header::{HeaderMap, COOKIE};
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use utils::internal_api::InternalApi;
-use utils::model::{Game, GameStore, GameType, PartialGame};
-use crate::stores::{make_identifier, make_request, Store};
+use utils::model::{Game, GameStore, GameType, OfferKind, PartialGame, Price};
+use crate::stores::{make_api_request, make_identifier, make_request, ScrapeError, ScrapeReport, SkipReason, Store};
 
-pub struct SteamStore;
+pub struct SteamStore {
+    max_results: usize,
+}
 
-const STEAM_STORE_URL: &str =
-    "https://store.steampowered.com/search/?cc=us&maxprice=free&specials=1";
+const STEAM_SEARCH_RESULTS_URL: &str =
+    "https://store.steampowered.com/search/results/?cc=us&maxprice=free&specials=1&infinite=1";
+const STEAM_FREE_PLAY_SEARCH_RESULTS_URL: &str =
+    "https://store.steampowered.com/search/results/?cc=us&filter=freeweekend&infinite=1";
+const STEAM_SEARCH_PAGE_SIZE: usize = 50;
+const STEAM_DEFAULT_MAX_RESULTS: usize = 500;
+// Free-to-play games are only news for a while after they launched
+const STEAM_FREE_TO_PLAY_LAUNCH_DAYS: u64 = 7;
 
 // Cookies that allow the bot to view games that are 18+
 const COOKIES: &str = "birthtime=788914801;lastagecheckage=1-January-1995;wants_mature_content=1;";
 
+/// Giveaways and free weekends are listed by different searches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Search {
+    /// Games discounted to free that can be kept
+    Giveaway,
+    /// Games that can be played for free for a while, their game page tells until when
+    FreePlay,
+}
+
+impl Search {
+    fn url(&self) -> &'static str {
+        match self {
+            Search::Giveaway => STEAM_SEARCH_RESULTS_URL,
+            Search::FreePlay => STEAM_FREE_PLAY_SEARCH_RESULTS_URL,
+        }
+    }
+}
+
+impl SteamStore {
+    pub(crate) fn new() -> Self {
+        let max_results = std::env::var("STEAM_MAX_RESULTS")
+            .ok()
+            .and_then(|max_results| max_results.parse().ok())
+            .unwrap_or(STEAM_DEFAULT_MAX_RESULTS);
+
+        Self { max_results }
+    }
+}
+
 #[async_trait::async_trait]
 impl Store for SteamStore {
-    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Vec<Game> {
-        let search_result_selector = Selector::parse(r#"div[id="search_resultsRows"] a"#).unwrap();
-        let game_discount_selector = Selector::parse(r#"div[class="discount_pct"]"#).unwrap();
+    fn name(&self) -> String {
+        String::from("Steam")
+    }
+
+    async fn get_games(&self, http: &Client, internal_api: &InternalApi) -> Result<ScrapeReport, ScrapeError> {
         let steam_url_regex =
             Regex::new(r#"https://store.steampowered.com/app/(?<app_id>[0-9]+)/[ -~]+/"#).unwrap();
 
-        let steam_search_url = Url::from_str(STEAM_STORE_URL).unwrap();
-
-        let response = match make_request(http, &steam_search_url, HeaderMap::new()).await {
-            Ok(response) => response,
-            Err(_) => return vec![],
-        };
+        let mut report = ScrapeReport::default();
+        let today = Utc::now().date_naive();
 
-        let game_urls = {
-            let mut urls = vec![];
+        let mut game_urls: Vec<(String, OfferKind)> = vec![];
 
-            let html = match response.text().await {
-                Ok(html) => html,
-                Err(_) => return vec![],
-            };
+        for search in [Search::Giveaway, Search::FreePlay] {
+            let fetch_page = |start| async move {
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
-            let document = Html::parse_document(&html);
+                let steam_search_url = Url::from_str(
+                    format!("{}&start={start}&count={STEAM_SEARCH_PAGE_SIZE}", search.url()).as_str()
+                ).unwrap();
 
-            for element in document.select(&search_result_selector) {
-                let discount_pct = match element.select(&game_discount_selector).next() {
-                    Some(discount_pct) => discount_pct,
-                    None => continue,
-                };
+                make_api_request::<model::SearchResultsPage>(http, &steam_search_url, HeaderMap::new())
+                    .await
+                    .map_err(ScrapeError::from)
+            };
 
-                if discount_pct.inner_html().as_str() != "-100%" {
-                    continue;
+            for (url, offer_kind) in walk_search_results(fetch_page, search, self.max_results, &mut report).await? {
+                if !game_urls.iter().any(|(known, _)| *known == url) {
+                    game_urls.push((url, offer_kind));
                 }
-
-                match element.attr("href") {
-                    Some(href) => {
-                        let mut string = href.to_string();
-                        let char = if string.contains('?') { "&" } else { "?" };
-                        string.push_str(format!("{char}cc=us").as_str());
-                        urls.push(string);
-                    }
-                    None => continue,
-                };
             }
+        }
 
-            urls
-        };
-
-        let mut free_games = Vec::with_capacity(game_urls.len());
-
-        for url in game_urls {
+        for (url, offer_kind) in game_urls {
             let id = match steam_url_regex
                 .captures(url.as_str())
                 .map(|captures| captures[1].to_string()) 
             {
                 Some(id) => id,
-                None => continue,
+                None => {
+                    log::error!("Couldn't find app id in url {url}");
+                    report.skip(SkipReason::ParseFailed);
+                    continue;
+                },
             };
 
             let partial_game = PartialGame {
@@ -85,24 +112,170 @@ impl Store for SteamStore {
                 Ok(false) => {
                     log::debug!("Game {id} does not exist.")
                 },
-                _ => {
+                Ok(true) => {
                     log::debug!("Game {id} already exists, skipping.");
+                    report.skip(SkipReason::AlreadyKnown);
+                    continue;
+                },
+                Err(e) => {
+                    log::error!("Couldn't check if game {id} exists: {e}");
+                    report.skip(SkipReason::RequestFailed);
                     continue;
                 }
             }
 
             let url = Url::from_str(url.as_str()).unwrap();
-            if let Some(game) = parse_game_page(http, &url).await {
-                free_games.push(game);
-                tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+            let html = match fetch_game_page(http, &url).await {
+                Ok(html) => html,
+                Err(e) => {
+                    log::error!("Error getting page {}: {e}", url.as_str());
+                    report.skip(SkipReason::RequestFailed);
+                    continue;
+                }
+            };
+
+            // The free play search also lists games whose event hasn't started or already ended, and
+            // most free-to-play games launched long ago
+            let is_offer = match offer_kind {
+                OfferKind::TemporaryPlay => is_free_play_page(&html),
+                OfferKind::FreeToPlay => is_free_to_play_launch(&html, today),
+                _ => true,
+            };
+            if !is_offer {
+                log::debug!("Game {id} isn't a {offer_kind} offer right now, skipping.");
+                report.skip(SkipReason::NotFree);
+                continue;
             }
+
+            match parse_game_page(&html, &url, today) {
+                Some(game) => report.games.push(game),
+                None => {
+                    log::error!("Couldn't parse game page {}", url.as_str());
+                    report.skip(SkipReason::ParseFailed);
+                    continue;
+                }
+            }
+
+            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        }
+
+        Ok(report)
+    }
+}
+
+/// Returns the game urls along with the kind of offer their row looks like.
+fn parse_search_results(html: &str, search: Search, report: &mut ScrapeReport) -> Vec<(String, OfferKind)> {
+    let search_result_selector = Selector::parse(r#"a.search_result_row"#).unwrap();
+
+    let mut urls = vec![];
+
+    let document = Html::parse_fragment(html);
+
+    for element in document.select(&search_result_selector) {
+        report.candidate();
+
+        let offer_kind = match search {
+            Search::Giveaway => match giveaway_row_kind(&element, report) {
+                Some(offer_kind) => offer_kind,
+                None => continue,
+            },
+            Search::FreePlay => OfferKind::TemporaryPlay,
+        };
+
+        match element.attr("href") {
+            Some(href) => {
+                let mut string = href.to_string();
+                let char = if string.contains('?') { "&" } else { "?" };
+                string.push_str(format!("{char}cc=us").as_str());
+                urls.push((string, offer_kind));
+            }
+            None => {
+                report.skip(SkipReason::ParseFailed);
+                continue;
+            },
+        };
+    }
+
+    urls
+}
+
+/// Whether a row of the giveaway search is discounted to free or a free-to-play game, otherwise the
+/// reason is recorded.
+fn giveaway_row_kind(element: &ElementRef, report: &mut ScrapeReport) -> Option<OfferKind> {
+    let game_discount_selector = Selector::parse(r#"div[class="discount_pct"]"#).unwrap();
+    let no_discount_selector = Selector::parse(r#"div.discount_block.no_discount"#).unwrap();
+
+    let discount_pct = match element.select(&game_discount_selector).next() {
+        Some(discount_pct) => discount_pct,
+        // Free-to-play games are listed without a discount, their game page tells when they launched
+        None if element.select(&no_discount_selector).next().is_some() => {
+            return Some(OfferKind::FreeToPlay);
+        },
+        None => {
+            report.skip(SkipReason::ParseFailed);
+            return None;
+        },
+    };
+
+    if discount_pct.inner_html().as_str() != "-100%" {
+        report.skip(SkipReason::NotFree);
+        return None;
+    }
+
+    Some(OfferKind::Keep)
+}
+
+/// Collects the game urls of all search result pages. The search page only renders the first
+/// rows, everything else is loaded through the infinite scroll endpoint, so `fetch_page` is called
+/// with the start of each page until the results are exhausted.
+async fn walk_search_results<F, Fut>(mut fetch_page: F, search: Search, max_results: usize, report: &mut ScrapeReport) -> Result<Vec<(String, OfferKind)>, ScrapeError>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<model::SearchResultsPage, ScrapeError>>,
+{
+    let mut game_urls = vec![];
+    let mut start = 0;
+
+    loop {
+        let page = fetch_page(start).await?;
+        let candidates = report.candidates;
+
+        game_urls.extend(parse_search_results(&page.results_html, search, report));
+
+        let rows = report.candidates - candidates;
+        if rows == 0 && start < page.total_count {
+            return Err(ScrapeError::Layout("search results not found".to_string()));
         }
 
-        free_games
+        match next_start(start, rows, page.total_count, max_results) {
+            Some(next) => start = next,
+            None => break,
+        }
     }
+
+    Ok(game_urls)
 }
 
-async fn parse_game_page(http: &Client, url: &Url) -> Option<Game> {
+/// Returns where the next page of search results starts, or `None` once all results (or as many
+/// as we are allowed to look at) have been seen.
+fn next_start(start: usize, rows: usize, total_count: usize, max_results: usize) -> Option<usize> {
+    let next = start + rows;
+
+    if rows == 0 || next >= total_count || next >= max_results {
+        return None;
+    }
+
+    Some(next)
+}
+
+async fn fetch_game_page(http: &Client, url: &Url) -> Result<String, reqwest::Error> {
+    let mut headers = HeaderMap::new();
+    headers.append(COOKIE, COOKIES.parse().unwrap());
+
+    make_request(http, url, headers).await?.text().await
+}
+
+fn parse_game_page(html: &str, url: &Url, today: NaiveDate) -> Option<Game> {
     let steam_url_regex =
         Regex::new(r#"https://store.steampowered.com/app/(?<app_id>[0-9]+)/[ -~]+/"#).unwrap();
 
@@ -115,14 +288,12 @@ async fn parse_game_page(http: &Client, url: &Url) -> Option<Game> {
         Selector::parse(r#"div[id="game_area_description"] h2"#).ok()?;
     let game_original_price_selector =
         Selector::parse(r#"div[class="discount_original_price"]"#).ok()?;
+    let game_regular_price_selector = Selector::parse(r#"div.game_purchase_price"#).ok()?;
     let game_offer_until_selector_quantity =
         Selector::parse(r#"p[class="game_purchase_discount_quantity "]"#).ok()?;
+    let header_image_selector = Selector::parse(r#"img.game_header_image_full"#).ok()?;
 
-    let mut headers = HeaderMap::new();
-    headers.append(COOKIE, COOKIES.parse().ok()?);
-
-    let response = make_request(http, url, headers).await.ok()?;
-    let document = Html::parse_document(&response.text().await.ok()?);
+    let document = Html::parse_document(html);
 
     let game_name = document.select(&game_name_selector).next()?.inner_html();
     let game_identifier = make_identifier(game_name.clone());
@@ -141,30 +312,29 @@ async fn parse_game_page(http: &Client, url: &Url) -> Option<Game> {
             _ => GameType::Unknown,
         }
     };
-    let game_original_price = document.select(&game_original_price_selector).next()?.inner_html();
-    let game_offer_until = {
-        let date_regex = Regex::new(r"(?<month>[a-zA-z]+) (?<day>[0-9]+)").unwrap();
-        let this_year = chrono::Utc::now().year();
-        let fmt = document.select(&game_offer_until_selector_quantity).next()?.inner_html();
-        let (month, day) = match date_regex.captures(fmt.as_str()) {
-            Some(captures) => (
-                captures.name("month")?.as_str(),
-                captures.name("day")?.as_str(),
-            ),
-            None => return None,
-        };
-        let mut offer_until = NaiveDate::parse_from_str(
-            format!("{day} {month} {this_year}").as_str(),
-            "%d %b %Y"
-        ).ok()?;
-        // We set the year of the offer to this year because steam doesn't add it. In the off-chance
-        // that that offer would be in the past because of it we just add one to the current year.
-        if offer_until < chrono::Utc::now().date_naive() {
-            offer_until =
-                NaiveDate::from_ymd_opt(this_year + 1, offer_until.month(), offer_until.day())?;
-        };
-        offer_until
+
+    let (offer_kind, game_offer_until) = if let Some(fmt) = find_free_play_until(&document) {
+        (OfferKind::TemporaryPlay, parse_offer_until(&fmt, today)?)
+    } else if let Some(quantity) = document.select(&game_offer_until_selector_quantity).next() {
+        (OfferKind::Keep, parse_offer_until(&quantity.inner_html(), today)?)
+    } else {
+        let released = find_free_to_play_launch(&document, today)?;
+        (OfferKind::FreeToPlay, released + Days::new(STEAM_FREE_TO_PLAY_LAUNCH_DAYS))
+    };
+
+    let game_original_price = match document.select(&game_original_price_selector).next() {
+        Some(original_price) => Price::parse(&original_price.inner_html()),
+        None if offer_kind == OfferKind::TemporaryPlay => {
+            Price::parse(document.select(&game_regular_price_selector).next()?.inner_html().trim())
+        },
+        // Free-to-play games never had a price
+        None if offer_kind == OfferKind::FreeToPlay => None,
+        None => return None,
     };
+    let image_url = document.select(&header_image_selector)
+        .next()
+        .and_then(|image| image.attr("src"))
+        .map(str::to_string);
 
     Some(Game {
         id: game_id,
@@ -173,7 +343,266 @@ async fn parse_game_page(http: &Client, url: &Url) -> Option<Game> {
         identifier: game_identifier,
         url: game_url,
         original_price: game_original_price,
+        offer_from: None,
         offer_until: game_offer_until,
         game_type,
+        redeem_on: None,
+        offer_kind,
+        image_url,
     })
 }
+
+fn parse_offer_until(fmt: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let date_regex = Regex::new(r"(?<month>[a-zA-z]+) (?<day>[0-9]+)").unwrap();
+    let this_year = today.year();
+    let (month, day) = match date_regex.captures(fmt) {
+        Some(captures) => (
+            captures.name("month")?.as_str(),
+            captures.name("day")?.as_str(),
+        ),
+        None => return None,
+    };
+    let date = format!("{day} {month} {this_year}");
+    // Discounts abbreviate the month while events spell it out
+    let mut offer_until = NaiveDate::parse_from_str(date.as_str(), "%d %b %Y")
+        .or_else(|_| NaiveDate::parse_from_str(date.as_str(), "%d %B %Y"))
+        .ok()?;
+    // We set the year of the offer to this year because steam doesn't add it. In the off-chance
+    // that that offer would be in the past because of it we just add one to the current year.
+    if offer_until < today {
+        offer_until =
+            NaiveDate::from_ymd_opt(this_year + 1, offer_until.month(), offer_until.day())?;
+    };
+    Some(offer_until)
+}
+
+fn is_free_play_page(html: &str) -> bool {
+    find_free_play_until(&Html::parse_document(html)).is_some()
+}
+
+/// Free weekends and similar events get their own purchase area with a "Play ... for free"
+/// banner instead of a discount, so they must not be announced as free to keep.
+fn find_free_play_until(document: &Html) -> Option<String> {
+    let free_play_banner_selector = Selector::parse(r#"div.game_area_purchase_game"#).unwrap();
+    let free_play_title_selector = Selector::parse(r#"h1"#).unwrap();
+    let free_play_until_selector = Selector::parse(r#"p"#).unwrap();
+
+    document.select(&free_play_banner_selector)
+        .find(|banner| banner.select(&free_play_title_selector)
+            .next()
+            .is_some_and(|title| is_free_play_banner(&title.text().collect::<String>())))
+        .and_then(|banner| banner.select(&free_play_until_selector)
+            .map(|p| p.text().collect::<String>())
+            .find(|text| text.to_lowercase().contains("until")))
+}
+
+fn is_free_to_play_launch(html: &str, today: NaiveDate) -> bool {
+    find_free_to_play_launch(&Html::parse_document(html), today).is_some()
+}
+
+/// Returns the release date of a free-to-play game that launched within the last few days.
+fn find_free_to_play_launch(document: &Html, today: NaiveDate) -> Option<NaiveDate> {
+    let price_selector = Selector::parse(r#"div.game_purchase_price"#).unwrap();
+    let release_date_selector = Selector::parse(r#"div.release_date div.date"#).unwrap();
+
+    let price = document.select(&price_selector).next()?.text().collect::<String>();
+    if !price.to_lowercase().contains("free") {
+        return None;
+    }
+
+    let release_date = document.select(&release_date_selector).next()?.inner_html();
+    // The US store writes "Jun 12, 2025", others "12 Jun, 2025"
+    let released = NaiveDate::parse_from_str(release_date.trim(), "%b %d, %Y")
+        .or_else(|_| NaiveDate::parse_from_str(release_date.trim(), "%d %b, %Y"))
+        .ok()?;
+
+    let days_since = (today - released).num_days();
+    (0..STEAM_FREE_TO_PLAY_LAUNCH_DAYS as i64).contains(&days_since).then_some(released)
+}
+
+fn is_free_play_banner(title: &str) -> bool {
+    let title = title.to_lowercase();
+    title.contains("free weekend") || title.contains("for free")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME_PAGE_KEEP_FIXTURE: &str = include_str!("../../../fixtures/steam/game_page_keep.html");
+    const GAME_PAGE_FREE_WEEKEND_FIXTURE: &str =
+        include_str!("../../../fixtures/steam/game_page_free_weekend.html");
+    const GAME_PAGE_FREE_TO_PLAY_FIXTURE: &str =
+        include_str!("../../../fixtures/steam/game_page_free_to_play.html");
+    const FREE_PLAY_SEARCH_RESULTS_FIXTURE: &str =
+        include_str!("../../../fixtures/steam/search_results_free_play.json");
+    const SEARCH_RESULTS_FIXTURES: [&str; 2] = [
+        include_str!("../../../fixtures/steam/search_results_0.json"),
+        include_str!("../../../fixtures/steam/search_results_3.json"),
+    ];
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 15).unwrap()
+    }
+
+    #[test]
+    fn test_parse_search_results() {
+        let page: model::SearchResultsPage = serde_json::from_str(SEARCH_RESULTS_FIXTURES[0]).unwrap();
+        let mut report = ScrapeReport::default();
+        let urls = parse_search_results(&page.results_html, Search::Giveaway, &mut report);
+
+        assert_eq!(report.candidates, 3);
+        assert!(report.skipped.is_empty());
+        assert_eq!(urls, vec![
+            ("https://store.steampowered.com/app/1111110/The_Game/?snr=1_7_7_2300_150_1&cc=us".to_string(), OfferKind::Keep),
+            ("https://store.steampowered.com/app/1111120/Free_To_Play_Shooter/?snr=1_7_7_2300_150_1&cc=us".to_string(), OfferKind::FreeToPlay),
+            ("https://store.steampowered.com/app/1111130/Another_Game/?snr=1_7_7_2300_150_1&cc=us".to_string(), OfferKind::Keep),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_search_results_without_discount() {
+        let page: model::SearchResultsPage = serde_json::from_str(SEARCH_RESULTS_FIXTURES[0]).unwrap();
+        let html = page.results_html.replacen(r#"<div class="discount_pct">-100%</div>"#, "", 1);
+        let mut report = ScrapeReport::default();
+        let urls = parse_search_results(&html, Search::Giveaway, &mut report);
+
+        assert_eq!(urls.len(), 2);
+        assert_eq!(report.skipped.get(&SkipReason::NotFree), None);
+        assert_eq!(report.skipped.get(&SkipReason::ParseFailed), Some(&1));
+    }
+
+    fn fetch_fixture(start: usize) -> std::future::Ready<Result<model::SearchResultsPage, ScrapeError>> {
+        let fixture = match start {
+            0 => SEARCH_RESULTS_FIXTURES[0],
+            3 => SEARCH_RESULTS_FIXTURES[1],
+            _ => panic!("requested a page that doesn't exist"),
+        };
+
+        std::future::ready(Ok(serde_json::from_str(fixture).unwrap()))
+    }
+
+    #[tokio::test]
+    async fn test_walk_all_pages() {
+        let mut report = ScrapeReport::default();
+        let mut pages = vec![];
+        let fetch_page = |start| {
+            pages.push(start);
+            fetch_fixture(start)
+        };
+        let urls = walk_search_results(fetch_page, Search::Giveaway, STEAM_DEFAULT_MAX_RESULTS, &mut report).await.unwrap();
+
+        assert_eq!(pages, vec![0, 3]);
+        assert_eq!(report.candidates, 4);
+        assert_eq!(urls.len(), 4);
+        assert!(urls[3].0.starts_with("https://store.steampowered.com/app/1111140/"));
+    }
+
+    #[tokio::test]
+    async fn test_walk_stops_at_max_results() {
+        let mut report = ScrapeReport::default();
+        let urls = walk_search_results(fetch_fixture, Search::Giveaway, 3, &mut report).await.unwrap();
+
+        assert_eq!(report.candidates, 3);
+        assert_eq!(urls.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_walk_changed_layout() {
+        let fetch_page = |_| std::future::ready(Ok(model::SearchResultsPage {
+            results_html: "<div class=\"search_results\"></div>".to_string(),
+            total_count: 4,
+        }));
+
+        assert!(matches!(
+            walk_search_results(fetch_page, Search::Giveaway, STEAM_DEFAULT_MAX_RESULTS, &mut ScrapeReport::default()).await,
+            Err(ScrapeError::Layout(_))
+        ));
+    }
+
+    #[test]
+    fn test_next_start() {
+        assert_eq!(next_start(0, 50, 120, 500), Some(50));
+        assert_eq!(next_start(100, 20, 120, 500), None);
+        assert_eq!(next_start(0, 50, 120, 50), None);
+        assert_eq!(next_start(0, 0, 120, 500), None);
+    }
+
+    #[test]
+    fn test_parse_keep_offer() {
+        let url = Url::from_str("https://store.steampowered.com/app/1111110/The_Game/?cc=us").unwrap();
+        let game = parse_game_page(GAME_PAGE_KEEP_FIXTURE, &url, today()).unwrap();
+
+        assert_eq!(game.id, "1111110");
+        assert_eq!(game.url, "https://store.steampowered.com/app/1111110/The_Game/");
+        assert_eq!(game.original_price, Some(Price::new(1999, "USD")));
+        assert_eq!(game.offer_kind, OfferKind::Keep);
+        assert_eq!(
+            game.image_url.as_deref(),
+            Some("https://shared.akamai.steamstatic.com/store_item_assets/steam/apps/1111110/header.jpg")
+        );
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 20).unwrap());
+    }
+
+    #[test]
+    fn test_parse_free_weekend() {
+        let url = Url::from_str("https://store.steampowered.com/app/1111130/Another_Game/?cc=us").unwrap();
+        let game = parse_game_page(GAME_PAGE_FREE_WEEKEND_FIXTURE, &url, today()).unwrap();
+
+        assert_eq!(game.title, "Another Game");
+        assert_eq!(game.original_price, Some(Price::new(2999, "USD")));
+        assert_eq!(game.offer_kind, OfferKind::TemporaryPlay);
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 23).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_free_weekend_search() {
+        let fetch_page = |_| std::future::ready(Ok(serde_json::from_str(FREE_PLAY_SEARCH_RESULTS_FIXTURE).unwrap()));
+        let mut report = ScrapeReport::default();
+        let urls = walk_search_results(fetch_page, Search::FreePlay, STEAM_DEFAULT_MAX_RESULTS, &mut report).await.unwrap();
+
+        // Free weekends aren't discounted, so every row is looked at
+        assert_eq!(report.candidates, 2);
+        assert!(report.skipped.is_empty());
+        assert_eq!(urls.len(), 2);
+
+        assert!(!is_free_play_page(GAME_PAGE_KEEP_FIXTURE));
+        assert!(is_free_play_page(GAME_PAGE_FREE_WEEKEND_FIXTURE));
+
+        let url = Url::from_str(&urls[0].0).unwrap();
+        let game = parse_game_page(GAME_PAGE_FREE_WEEKEND_FIXTURE, &url, today()).unwrap();
+
+        assert_eq!(game.id, "1111130");
+        assert_eq!(game.url, "https://store.steampowered.com/app/1111130/Another_Game/");
+        assert_eq!(game.offer_kind, OfferKind::TemporaryPlay);
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 23).unwrap());
+    }
+
+    #[test]
+    fn test_parse_free_to_play_launch() {
+        let url = Url::from_str("https://store.steampowered.com/app/1111120/Free_To_Play_Shooter/?cc=us").unwrap();
+        let game = parse_game_page(GAME_PAGE_FREE_TO_PLAY_FIXTURE, &url, today()).unwrap();
+
+        assert_eq!(game.title, "Free To Play Shooter");
+        assert_eq!(game.original_price, None);
+        assert_eq!(game.offer_kind, OfferKind::FreeToPlay);
+        assert_eq!(game.offer_until, NaiveDate::from_ymd_opt(2025, 6, 19).unwrap());
+
+        assert!(is_free_to_play_launch(GAME_PAGE_FREE_TO_PLAY_FIXTURE, today()));
+        assert!(!is_free_to_play_launch(GAME_PAGE_KEEP_FIXTURE, today()));
+    }
+
+    #[test]
+    fn test_free_to_play_launch_window() {
+        let launched = |today| is_free_to_play_launch(GAME_PAGE_FREE_TO_PLAY_FIXTURE, today);
+
+        assert!(launched(NaiveDate::from_ymd_opt(2025, 6, 12).unwrap()));
+        assert!(launched(NaiveDate::from_ymd_opt(2025, 6, 18).unwrap()));
+        // Neither before the launch nor long after it
+        assert!(!launched(NaiveDate::from_ymd_opt(2025, 6, 11).unwrap()));
+        assert!(!launched(NaiveDate::from_ymd_opt(2025, 6, 19).unwrap()));
+
+        let url = Url::from_str("https://store.steampowered.com/app/1111120/Free_To_Play_Shooter/?cc=us").unwrap();
+        assert!(parse_game_page(GAME_PAGE_FREE_TO_PLAY_FIXTURE, &url, NaiveDate::from_ymd_opt(2025, 9, 1).unwrap()).is_none());
+    }
+}
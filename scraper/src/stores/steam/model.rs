@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SearchResultsPage {
+    // pub success: u8,
+    pub results_html: String,
+    pub total_count: usize,
+    // pub start: usize,
+}
@@ -1,8 +1,8 @@
 use reqwest::header::HeaderMap;
-use reqwest::Response;
+use reqwest::{Response, StatusCode, Url};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use crate::model::{Game, PartialGame, PostedPlatform};
+use crate::model::{Game, GamesPage, PartialGame, PostedPlatform};
 
 type Error = Box<dyn std::error::Error>;
 
@@ -24,8 +24,33 @@ impl InternalApi {
         }
     }
 
-    fn build_url(&self, endpoint: &str) -> String {
-        format!("{}/{}", self.api_url, endpoint)
+    /// Builds a `/v2` URL, every segment is percent-encoded on its own.
+    fn build_url(&self, segments: &[&str]) -> Result<Url, Error> {
+        let mut url = Url::parse(&self.api_url)?;
+        url.path_segments_mut()
+            .map_err(|_| format!("Invalid API URL: {}", self.api_url))?
+            .pop_if_empty()
+            .push("v2")
+            .extend(segments);
+
+        Ok(url)
+    }
+
+    fn game_url(&self, game: &PartialGame) -> Result<Url, Error> {
+        self.build_url(&["games", &game.store.to_string(), &game.id])
+    }
+
+    fn post_url(&self, posted_platform: &PostedPlatform) -> Result<Url, Error> {
+        let mut url = self.build_url(&[
+            "games",
+            &posted_platform.game_store.to_string(),
+            &posted_platform.game_id,
+            "posts",
+            &posted_platform.platform,
+        ])?;
+        url.query_pairs_mut().append_pair("kind", &posted_platform.kind.to_string());
+
+        Ok(url)
     }
     
     fn get_headers(&self) -> HeaderMap {
@@ -34,77 +59,98 @@ impl InternalApi {
         headers
     }
 
-    async fn extract_item<D, B>(&self, endpoint: &str, body: Option<&B>) -> Result<D, Error>
-    where D: DeserializeOwned, B: Serialize
+    async fn extract_item<D>(&self, url: Url) -> Result<D, Error>
+    where D: DeserializeOwned
     {
-        let mut builder = self.http_client.get(self.build_url(endpoint)).headers(self.get_headers());
-        if let Some(body) = body {
-            builder = builder.json(body);
+        let res = self.http_client.get(url.clone()).headers(self.get_headers()).send().await?;
+        if !res.status().is_success() {
+            return Err(format!("extract_item failed on \"{}\": {}", url.path(), res.text().await?).into());
         }
-        match builder.send().await {
-            Ok(res) if res.status().is_success() => {
-                let text = res.text().await?;
-                Ok(serde_json::from_str(&text)?)
-            },
-            Ok(res) => {
-                Err(
-                    format!(
-                        "extract_item failed on endpoint \"{}\": {}",
-                        endpoint,
-                        res.text().await?
-                    ).into())
-            },
-            Err(e) => {
-                Err(e.into())
-            }
+
+        let text = res.text().await?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Maps `404 Not Found` to `false` instead of an error.
+    async fn item_exists(&self, url: Url) -> Result<bool, Error> {
+        let res = self.http_client.get(url.clone()).headers(self.get_headers()).send().await?;
+        match res.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            _ => Err(format!("item_exists failed on \"{}\": {}", url.path(), res.text().await?).into()),
         }
     }
 
-    async fn post_item<D>(&self, endpoint: &str, item: &D) -> Result<Response, Error>
+    /// Maps `409 Conflict` to success too, the item was already created then.
+    async fn post_item<D>(&self, url: Url, item: Option<&D>) -> Result<(), Error>
     where D: Serialize
     {
-        Ok(self.http_client.post(self.build_url(endpoint)).headers(self.get_headers()).json(item).send().await?)
+        let mut builder = self.http_client.post(url.clone()).headers(self.get_headers());
+        if let Some(item) = item {
+            builder = builder.json(item);
+        }
+
+        let res = builder.send().await?;
+        if !is_created(res.status()) {
+            return Err(format!("post_item failed on \"{}\" with {}: {}", url.path(), res.status(), res.text().await?).into());
+        }
+
+        Ok(())
     }
 
-    async fn delete_item<D>(&self, endpoint: &str, item: D) -> Result<Response, Error>
-    where D: Serialize
-    {
-        Ok(self.http_client.delete(self.build_url(endpoint)).headers(self.get_headers()).json(&item).send().await?)
+    async fn delete_item(&self, url: Url) -> Result<Response, Error> {
+        Ok(self.http_client.delete(url).headers(self.get_headers()).send().await?)
     }
 
     pub async fn get_all_games(&self) -> Result<Vec<Game>, Error> {
         log::debug!("Getting all games");
-        self.extract_item::<Vec<Game>, ()>("", None).await
+        let url = self.build_url(&["games"])?;
+        Ok(self.extract_item::<GamesPage>(url).await?.games)
     }
 
     pub async fn get_free_games(&self) -> Result<Vec<Game>, Error> {
         log::debug!("Getting all free games");
-        self.extract_item::<Vec<Game>, ()>("free", None).await
+        let url = self.build_url(&["games", "free"])?;
+        Ok(self.extract_item::<GamesPage>(url).await?.games)
+    }
+
+    pub async fn get_upcoming_games(&self) -> Result<Vec<Game>, Error> {
+        log::debug!("Getting all upcoming games");
+        let url = self.build_url(&["games", "upcoming"])?;
+        Ok(self.extract_item::<GamesPage>(url).await?.games)
     }
 
     pub async fn get_game(&self, game: &PartialGame) -> Result<bool, Error> {
         log::debug!("Check if item exists");
-        self.extract_item("game", Some(game)).await
+        let url = self.game_url(game)?;
+        self.item_exists(url).await
     }
 
-    pub async fn post_game(&self, game: &Game) -> Result<Response, Error> {
+    /// Succeeds if the game already exists.
+    pub async fn post_game(&self, game: &Game) -> Result<(), Error> {
         log::debug!("Posting game");
-        self.post_item("game", game).await
+        let url = self.build_url(&["games"])?;
+        self.post_item(url, Some(game)).await
     }
 
+    /// Responds with `404 Not Found` if there is no such game.
     pub async fn delete_game(&self, game: PartialGame) -> Result<Response, Error> {
         log::debug!("Delete game");
-        self.delete_item("game", game).await
+        let url = self.game_url(&game)?;
+        self.delete_item(url).await
     }
 
     pub async fn is_posted(&self, posted_platform: &PostedPlatform) -> Result<bool, Error> {
         log::debug!("Check if game is posted");
-        self.extract_item("posted", Some(posted_platform)).await
+        let url = self.post_url(posted_platform)?;
+        self.item_exists(url).await
     }
 
-    pub async fn post_posted(&self, posted_platform: &PostedPlatform) -> Result<Response, Error> {
+    /// Succeeds if the game was already posted on the platform.
+    pub async fn post_posted(&self, posted_platform: &PostedPlatform) -> Result<(), Error> {
         log::debug!("Post post");
-        self.post_item("posted", posted_platform).await
+        let url = self.post_url(posted_platform)?;
+        self.post_item::<()>(url, None).await
     }
 }
 
@@ -113,7 +159,13 @@ pub async fn wait_for_internal_api(internal_api: &InternalApi) -> Result<(), Err
     loop {
         log::info!("Waiting for API...");
 
-        match internal_api.get_all_games().await {
+        // A single game is enough to know the API and its database are up
+        let url = internal_api.build_url(&["games"]).map(|mut url| {
+            url.query_pairs_mut().append_pair("limit", "1");
+            url
+        })?;
+
+        match internal_api.extract_item::<GamesPage>(url).await {
             Ok(_) => {
                 log::info!("API ready");
                 return Ok(());
@@ -130,3 +182,41 @@ pub async fn wait_for_internal_api(internal_api: &InternalApi) -> Result<(), Err
         tokio::time::sleep(std::time::Duration::from_secs(RETRY_DELAY)).await;
     }
 }
+
+fn is_created(status: StatusCode) -> bool {
+    status.is_success() || status == StatusCode::CONFLICT
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{GameStore, PostKind};
+    use super::*;
+
+    #[test]
+    fn test_build_url() {
+        let api = InternalApi::new("http://api:8080/".to_string(), "token".to_string());
+
+        assert_eq!(api.build_url(&["games"]).unwrap().as_str(), "http://api:8080/v2/games");
+        assert_eq!(
+            api.game_url(&PartialGame { id: "a/b c".to_string(), store: GameStore::ItchIo }).unwrap().as_str(),
+            "http://api:8080/v2/games/ItchIo/a%2Fb%20c",
+        );
+        assert_eq!(
+            api.post_url(&PostedPlatform {
+                platform: "Twitter".to_string(),
+                game_id: "123".to_string(),
+                game_store: GameStore::Steam,
+                kind: PostKind::Upcoming,
+            }).unwrap().as_str(),
+            "http://api:8080/v2/games/Steam/123/posts/Twitter?kind=Upcoming",
+        );
+    }
+
+    #[test]
+    fn test_is_created() {
+        assert!(is_created(StatusCode::CREATED));
+        assert!(is_created(StatusCode::CONFLICT));
+        assert!(!is_created(StatusCode::NOT_FOUND));
+        assert!(!is_created(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+}
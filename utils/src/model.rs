@@ -9,9 +9,122 @@ pub struct Game {
     pub title: String,
     pub identifier: String,
     pub url: String,
-    pub original_price: String,
+    pub original_price: Option<Price>,
+    #[serde(default)]
+    pub offer_from: Option<NaiveDate>,
     pub offer_until: NaiveDate,
     pub game_type: GameType,
+    #[serde(default)]
+    pub redeem_on: Option<Launcher>,
+    #[serde(default)]
+    pub offer_kind: OfferKind,
+    #[serde(default)]
+    pub image_url: Option<String>,
+}
+
+/// A price in the smallest unit of its currency, e.g. cents for USD.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Price {
+    pub amount_minor: i64,
+    /// ISO-4217 currency code
+    pub currency: String,
+}
+
+impl Price {
+    pub fn new(amount_minor: i64, currency: &str) -> Self {
+        Self {
+            amount_minor,
+            currency: currency.to_uppercase(),
+        }
+    }
+
+    /// Converts an amount in the major unit of the currency, e.g. dollars for USD.
+    pub fn from_major(amount: f64, currency: &str) -> Self {
+        let factor = 10_f64.powi(minor_digits(currency) as i32);
+        Self::new((amount * factor).round() as i64, currency)
+    }
+
+    /// Parses a price the way stores display it, e.g. "$19.99", "19,99€" or "1.234,50 EUR". A
+    /// currency code wins over a symbol, a bare "$" is taken for US dollars.
+    pub fn parse(display: &str) -> Option<Self> {
+        let display = display.trim();
+
+        let currency = display.split(|c: char| !c.is_ascii_alphabetic())
+            .find(|code| code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()))
+            .map(str::to_string)
+            .or_else(|| {
+                CURRENCY_SYMBOLS.iter()
+                    .find(|(symbol, _)| display.contains(symbol))
+                    .map(|(_, currency)| currency.to_string())
+            })?;
+
+        let number: String = display.chars()
+            .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+            .collect();
+        let digits = minor_digits(&currency);
+
+        // Whatever separator is followed by exactly as many digits as the currency has minor
+        // units is the decimal separator, every other one separates thousands.
+        let (major, minor) = match number.rfind(['.', ',']) {
+            Some(index) if digits > 0 && number.len() - index - 1 == digits as usize => {
+                (&number[..index], &number[index + 1..])
+            },
+            _ => (number.as_str(), ""),
+        };
+
+        let major: i64 = major.replace(['.', ','], "").parse().ok()?;
+        let minor: i64 = if minor.is_empty() { 0 } else { minor.parse().ok()? };
+
+        Some(Self::new(major * 10_i64.pow(digits) + minor, &currency))
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = minor_digits(&self.currency);
+        let factor = 10_i64.pow(digits);
+        let amount = if digits == 0 {
+            (self.amount_minor / factor).to_string()
+        } else {
+            format!("{}.{:0width$}", self.amount_minor / factor, self.amount_minor % factor, width = digits as usize)
+        };
+
+        match CURRENCY_SYMBOLS.iter().find(|(_, currency)| *currency == self.currency) {
+            Some((symbol, _)) => write!(f, "{symbol}{amount}"),
+            None => write!(f, "{amount} {}", self.currency),
+        }
+    }
+}
+
+/// Dollars of other countries come first, they would be taken for US dollars otherwise.
+const CURRENCY_SYMBOLS: [(&str, &str); 11] = [
+    ("CA$", "CAD"),
+    ("A$", "AUD"),
+    ("AU$", "AUD"),
+    ("NZ$", "NZD"),
+    ("HK$", "HKD"),
+    ("MX$", "MXN"),
+    ("R$", "BRL"),
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+];
+
+fn minor_digits(currency: &str) -> u32 {
+    match currency {
+        "JPY" | "KRW" => 0,
+        _ => 2,
+    }
+}
+
+/// One page of a game listing, `next_cursor` continues after its last game.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GamesPage {
+    pub games: Vec<Game>,
+    /// Number of games matching the filters across all pages
+    pub total: i64,
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -20,7 +133,7 @@ pub struct PartialGame {
     pub store: GameStore,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub enum GameStore {
     #[serde(rename = "Steam")]
     Steam,
@@ -28,6 +141,12 @@ pub enum GameStore {
     EpicGames,
     #[serde(rename = "GOG")]
     Gog,
+    #[serde(rename = "ItchIo")]
+    ItchIo,
+    #[serde(rename = "Humble")]
+    Humble,
+    #[serde(rename = "PrimeGaming")]
+    PrimeGaming,
     #[serde(other)]
     Unknown,
 }
@@ -38,6 +157,9 @@ impl fmt::Display for GameStore {
             GameStore::Steam => write!(f, "Steam"),
             GameStore::EpicGames => write!(f, "EpicGames"),
             GameStore::Gog => write!(f, "GOG"),
+            GameStore::ItchIo => write!(f, "ItchIo"),
+            GameStore::Humble => write!(f, "Humble"),
+            GameStore::PrimeGaming => write!(f, "PrimeGaming"),
             GameStore::Unknown => write!(f, "Unknown"),
         }
     }
@@ -49,6 +171,9 @@ impl From<String> for GameStore {
             "steam" => GameStore::Steam,
             "epicgames" => GameStore::EpicGames,
             "gog" => GameStore::Gog,
+            "itchio" => GameStore::ItchIo,
+            "humble" => GameStore::Humble,
+            "primegaming" => GameStore::PrimeGaming,
             _ => GameStore::Unknown,
         }
     }
@@ -90,9 +215,151 @@ impl From<String> for GameType {
     }
 }
 
+/// What the player gets out of an offer.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub enum OfferKind {
+    /// The game can be claimed and kept forever.
+    #[default]
+    Keep,
+    /// The game can only be played for free until the offer ends, e.g. Steam free weekends.
+    TemporaryPlay,
+    /// The game launched as free-to-play and stays free.
+    FreeToPlay,
+    #[serde(other)]
+    Unknown,
+}
+
+impl fmt::Display for OfferKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OfferKind::Keep => write!(f, "Keep"),
+            OfferKind::TemporaryPlay => write!(f, "TemporaryPlay"),
+            OfferKind::FreeToPlay => write!(f, "FreeToPlay"),
+            OfferKind::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+impl From<String> for OfferKind {
+    fn from(s: String) -> Self {
+        match s.to_lowercase().as_str() {
+            "keep" => OfferKind::Keep,
+            "temporaryplay" => OfferKind::TemporaryPlay,
+            "freetoplay" => OfferKind::FreeToPlay,
+            _ => OfferKind::Unknown,
+        }
+    }
+}
+
+/// The launcher a game is redeemed on when the store only hands out a key or code for it. It's
+/// stored under its serde name, rows that were stored as GOG before still parse.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum Launcher {
+    AmazonGames,
+    EpicGames,
+    Gog,
+    LegacyGames,
+    #[serde(other)]
+    Unknown,
+}
+
+impl fmt::Display for Launcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Launcher::AmazonGames => write!(f, "AmazonGames"),
+            Launcher::EpicGames => write!(f, "EpicGames"),
+            Launcher::Gog => write!(f, "Gog"),
+            Launcher::LegacyGames => write!(f, "LegacyGames"),
+            Launcher::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+impl From<String> for Launcher {
+    fn from(s: String) -> Self {
+        match s.to_lowercase().as_str() {
+            "amazongames" => Launcher::AmazonGames,
+            "epicgames" => Launcher::EpicGames,
+            "gog" => Launcher::Gog,
+            "legacygames" => Launcher::LegacyGames,
+            _ => Launcher::Unknown,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PostedPlatform {
     pub platform: String,
     pub game_id: String,
     pub game_store: GameStore,
+    #[serde(default)]
+    pub kind: PostKind,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub enum PostKind {
+    #[default]
+    Free,
+    Upcoming,
+}
+
+impl fmt::Display for PostKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PostKind::Free => write!(f, "Free"),
+            PostKind::Upcoming => write!(f, "Upcoming"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_price() {
+        assert_eq!(Price::parse("$19.99"), Some(Price::new(1999, "USD")));
+        assert_eq!(Price::parse("19,99€"), Some(Price::new(1999, "EUR")));
+        assert_eq!(Price::parse("1.234,50 EUR"), Some(Price::new(123450, "EUR")));
+        assert_eq!(Price::parse("$1,234"), Some(Price::new(123400, "USD")));
+        assert_eq!(Price::parse("¥1,980"), Some(Price::new(1980, "JPY")));
+        assert_eq!(Price::parse("Free"), None);
+    }
+
+    #[test]
+    fn test_parse_other_dollars() {
+        assert_eq!(Price::parse("CA$24.99"), Some(Price::new(2499, "CAD")));
+        assert_eq!(Price::parse("A$29.95"), Some(Price::new(2995, "AUD")));
+        assert_eq!(Price::parse("AU$29.95"), Some(Price::new(2995, "AUD")));
+        assert_eq!(Price::parse("R$ 37,99"), Some(Price::new(3799, "BRL")));
+        assert_eq!(Price::parse("$19.99 CAD"), Some(Price::new(1999, "CAD")));
+        assert_eq!(Price::parse("US$19.99"), Some(Price::new(1999, "USD")));
+        assert_eq!(Price::new(2499, "CAD").to_string(), "CA$24.99");
+    }
+
+    #[test]
+    fn test_offer_kind() {
+        assert_eq!(OfferKind::from("FreeToPlay".to_string()), OfferKind::FreeToPlay);
+        assert_eq!(OfferKind::from(OfferKind::TemporaryPlay.to_string()), OfferKind::TemporaryPlay);
+        assert_eq!(OfferKind::from("Lease".to_string()), OfferKind::Unknown);
+        assert_eq!(serde_json::from_str::<OfferKind>(r#""Lease""#).unwrap(), OfferKind::Unknown);
+    }
+
+    #[test]
+    fn test_launcher() {
+        for launcher in [Launcher::AmazonGames, Launcher::EpicGames, Launcher::Gog, Launcher::LegacyGames, Launcher::Unknown] {
+            assert_eq!(serde_json::to_string(&launcher).unwrap(), format!(r#""{launcher}""#));
+            assert_eq!(Launcher::from(launcher.to_string()), launcher);
+        }
+
+        assert_eq!(Launcher::from("GOG".to_string()), Launcher::Gog);
+        assert_eq!(serde_json::from_str::<Launcher>(r#""Battlenet""#).unwrap(), Launcher::Unknown);
+    }
+
+    #[test]
+    fn test_display_price() {
+        assert_eq!(Price::new(1999, "USD").to_string(), "$19.99");
+        assert_eq!(Price::new(905, "CHF").to_string(), "9.05 CHF");
+        assert_eq!(Price::from_major(4.99, "eur").to_string(), "€4.99");
+    }
 }